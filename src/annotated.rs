@@ -0,0 +1,145 @@
+use crate::{ParseError, ParsedAddr, parse_full};
+
+/// An address from a config line, with its `;key=value` attributes and any
+/// trailing comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotated {
+    pub addr: ParsedAddr,
+    pub attributes: Vec<(String, String)>,
+    pub comment: Option<String>,
+}
+
+/// Parses `address[;key=value...] [# comment]`, e.g.
+/// `10.0.0.1:80;weight=5 # primary`. Same as [`parse_annotated_with`] using only
+/// `#` as the comment marker.
+pub fn parse_annotated(input: &str) -> Result<Annotated, ParseError> {
+    parse_annotated_with(input, &['#'])
+}
+
+/// [`parse_annotated`] with a choice of comment markers, such as `&['#', ';']`
+/// for INI-style files. Everything from the first marker on is the comment.
+/// Because `;` also introduces attributes, making it a comment marker turns
+/// attributes off: `10.0.0.1:80 ; weight=5` is then an address and a comment.
+pub fn parse_annotated_with(
+    input: &str,
+    comment_markers: &[char],
+) -> Result<Annotated, ParseError> {
+    let (body, comment) = match input.find(comment_markers) {
+        Some(pos) => {
+            let marker_len = input[pos..].chars().next().map_or(1, char::len_utf8);
+            (
+                &input[..pos],
+                Some(input[pos + marker_len..].trim().to_string()),
+            )
+        }
+        None => (input, None),
+    };
+
+    // With `;` as a comment marker the body can't contain one, so no attributes
+    let mut parts = body.split(';');
+    let addr_str = parts.next().unwrap_or("");
+    let mut attributes = Vec::new();
+    for part in parts {
+        let attribute = part.trim();
+        match attribute.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                attributes.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            _ => return Err(ParseError::InvalidAttribute(attribute.to_string())),
+        }
+    }
+
+    Ok(Annotated {
+        addr: parse_full(addr_str)?,
+        attributes,
+        comment,
+    })
+}
+
+/// Parses a `key = value` config line such as `listen = 0.0.0.0:8080`,
+/// splitting on the first `=` and returning the key with the parsed value. A
+/// line without `=` or with an empty key is [`ParseError::InvalidAttribute`].
+pub fn parse_kv_line(input: &str) -> Result<(String, ParsedAddr), ParseError> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), parse_full(value.trim())?))
+        }
+        _ => Err(ParseError::InvalidAttribute(input.trim().to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IpVersion;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn annotated() {
+        let result = parse_annotated("10.0.0.1:80;weight=5; role = primary # main server").unwrap();
+        assert_eq!(result.addr.ip, IpVersion::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(result.addr.port, Some(80));
+        assert_eq!(
+            result.attributes,
+            vec![
+                ("weight".to_string(), "5".to_string()),
+                ("role".to_string(), "primary".to_string())
+            ]
+        );
+        assert_eq!(result.comment.as_deref(), Some("main server"));
+
+        let result = parse_annotated("[::1]:22").unwrap();
+        assert!(result.attributes.is_empty());
+        assert_eq!(result.comment, None);
+
+        assert!(matches!(
+            parse_annotated("10.0.0.1;weight"),
+            Err(ParseError::InvalidAttribute(_))
+        ));
+    }
+
+    #[test]
+    fn ini_comments() {
+        let result = parse_annotated_with("10.0.0.1:80 ; primary", &['#', ';']).unwrap();
+        assert_eq!(result.addr.port, Some(80));
+        assert!(result.attributes.is_empty());
+        assert_eq!(result.comment.as_deref(), Some("primary"));
+
+        let result = parse_annotated_with("10.0.0.1:80 # primary ; backup", &['#', ';']).unwrap();
+        assert_eq!(result.comment.as_deref(), Some("primary ; backup"));
+
+        // Without `;` as a marker it's an attribute, and this one is malformed
+        assert!(parse_annotated("10.0.0.1:80 ; primary").is_err());
+    }
+
+    #[test]
+    fn kv_line() {
+        let test_cases = vec![
+            ("listen = 0.0.0.0:8080", "listen", "0.0.0.0:8080"),
+            (
+                "  upstream.primary=[::1]:443  ",
+                "upstream.primary",
+                "[::1]:443",
+            ),
+            ("dns =\t10.0.0.53", "dns", "10.0.0.53"),
+        ];
+        for (input, key, addr) in test_cases {
+            let (parsed_key, parsed_addr) = parse_kv_line(input).unwrap();
+            assert_eq!(
+                (parsed_key.as_str(), parsed_addr.to_string()),
+                (key, addr.to_string())
+            );
+        }
+        let test_cases = vec!["listen 0.0.0.0:8080", " = 10.0.0.1"];
+        for input in test_cases {
+            assert!(matches!(
+                parse_kv_line(input),
+                Err(ParseError::InvalidAttribute(_))
+            ));
+        }
+        assert!(matches!(
+            parse_kv_line("listen = nowhere"),
+            Err(ParseError::InvalidAddress(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{IpVersion, ParsedAddr};
+
+/// Assembles an endpoint string piece by piece, the write side of
+/// [`parse_full`](crate::parse_full).
+///
+/// With a scheme the result is a URL, so IPv6 is always bracketed and a zone is
+/// percent-encoded (`https://[fe80::1%25eth0]:443`). Without one it is the
+/// [`ParsedAddr`] display form (`[fe80::1%eth0]:443`). A zone on an IPv4
+/// address is ignored.
+#[derive(Debug, Clone)]
+pub struct EndpointBuilder {
+    addr: ParsedAddr,
+}
+
+impl EndpointBuilder {
+    pub fn new(ip: IpVersion) -> Self {
+        EndpointBuilder {
+            addr: ParsedAddr::new(ip, None),
+        }
+    }
+
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.addr.scheme_original = Some(scheme.into());
+        self
+    }
+
+    /// Replaces the address given to [`new`](EndpointBuilder::new).
+    pub fn ip(mut self, ip: IpVersion) -> Self {
+        self.addr.ip = ip;
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.addr.port = Some(port);
+        self
+    }
+
+    pub fn zone(mut self, zone: impl Into<String>) -> Self {
+        self.addr = self.addr.with_zone(zone);
+        self
+    }
+
+    pub fn build(&self) -> String {
+        let Some(scheme) = &self.addr.scheme_original else {
+            return self.addr.to_string();
+        };
+        let mut url = format!(
+            "{scheme}{}{}",
+            crate::components::scheme_separator(scheme),
+            self.addr.host_str()
+        );
+        if let Some(port) = self.addr.port_str() {
+            url.push(':');
+            url.push_str(&port);
+        }
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let v6 = IpVersion::V6("fe80::1".parse().unwrap());
+        let full = EndpointBuilder::new(v6.clone())
+            .scheme("https")
+            .zone("eth0")
+            .port(8443);
+        assert_eq!(full.build(), "https://[fe80::1%25eth0]:8443");
+        let parsed = crate::parse_full(&full.build()).unwrap();
+        assert_eq!(parsed.ip, v6);
+        assert_eq!(parsed.port, Some(8443));
+        assert_eq!(parsed.zone.as_deref(), Some("eth0"));
+        assert_eq!(parsed.scheme_original.as_deref(), Some("https"));
+
+        let test_cases = vec![
+            (EndpointBuilder::new(v6.clone()), "fe80::1"),
+            (
+                EndpointBuilder::new(v6.clone()).scheme("http"),
+                "http://[fe80::1]",
+            ),
+            (
+                EndpointBuilder::new(v6).zone("eth0").port(80),
+                "[fe80::1%eth0]:80",
+            ),
+            (
+                EndpointBuilder::new(IpVersion::V4("10.0.0.1".parse().unwrap())).port(22),
+                "10.0.0.1:22",
+            ),
+        ];
+        for (builder, expected) in test_cases {
+            assert_eq!(builder.build(), expected);
+        }
+
+        let sip = EndpointBuilder::new(IpVersion::V6("::1".parse().unwrap()))
+            .scheme("sip")
+            .port(5060);
+        assert_eq!(sip.build(), "sip:[::1]:5060");
+        let parsed = crate::parse_full(&sip.build()).unwrap();
+        assert_eq!(parsed.scheme_original.as_deref(), Some("sip"));
+        assert_eq!(parsed.port, Some(5060));
+    }
+}
@@ -0,0 +1,68 @@
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+
+use crate::{ParseError, ParsedAddr, parse_full};
+
+/// A memoizing [`parse_full`] for workloads that see the same inputs over and
+/// over, with the `lru` feature. Results, errors included, are kept for the
+/// `capacity` most recently used inputs.
+pub struct ParseCache {
+    cache: LruCache<String, Result<ParsedAddr, ParseError>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ParseCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        ParseCache {
+            cache: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn parse(&mut self, input: &str) -> Result<ParsedAddr, ParseError> {
+        if let Some(result) = self.cache.get(input) {
+            self.hits += 1;
+            return result.clone();
+        }
+        self.misses += 1;
+        let result = parse_full(input);
+        self.cache.put(input.to_string(), result.clone());
+        result
+    }
+
+    /// How many calls were answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// How many calls had to parse.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_hits() {
+        let mut cache = ParseCache::new(NonZeroUsize::new(2).unwrap());
+        let first = cache.parse("10.0.0.1:80");
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+        assert_eq!(cache.parse("10.0.0.1:80"), first);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        assert!(cache.parse("not an address").is_err());
+        assert!(cache.parse("not an address").is_err());
+        assert_eq!((cache.hits(), cache.misses()), (2, 2));
+
+        // A third input pushes out the least recently used, `10.0.0.1:80`
+        cache.parse("::1").unwrap();
+        cache.parse("10.0.0.1:80").unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (2, 4));
+    }
+}
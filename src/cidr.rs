@@ -0,0 +1,936 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::{IpVersion, ParseError, validate_zone};
+
+// Netmask for an IPv4 prefix length, clamping anything over 32 to a host mask
+pub(crate) fn ipv4_mask(prefix: u8) -> u32 {
+    match prefix {
+        0 => 0,
+        p if p >= 32 => u32::MAX,
+        p => u32::MAX << (32 - p),
+    }
+}
+
+pub(crate) fn mask_ipv4(addr: &Ipv4Addr, prefix: u8) -> Ipv4Addr {
+    Ipv4Addr::from(u32::from(*addr) & ipv4_mask(prefix))
+}
+
+pub(crate) fn ipv6_mask(prefix: u8) -> u128 {
+    match prefix {
+        0 => 0,
+        p if p >= 128 => u128::MAX,
+        p => u128::MAX << (128 - p),
+    }
+}
+
+pub(crate) fn mask_ipv6(addr: &Ipv6Addr, prefix: u8) -> Ipv6Addr {
+    Ipv6Addr::from(u128::from(*addr) & ipv6_mask(prefix))
+}
+
+// Whether `addr` falls inside `net/prefix`; mismatched families never match
+pub(crate) fn in_network(addr: &IpVersion, net: &IpVersion, prefix: u8) -> bool {
+    match (addr, net) {
+        (IpVersion::V4(a), IpVersion::V4(n)) => mask_ipv4(a, prefix) == mask_ipv4(n, prefix),
+        (IpVersion::V6(a), IpVersion::V6(n)) => mask_ipv6(a, prefix) == mask_ipv6(n, prefix),
+        _ => false,
+    }
+}
+
+// Address as an integer plus its bit width, so the network maths can be shared
+pub(crate) fn to_bits(addr: &IpVersion) -> (u128, u8) {
+    match addr {
+        IpVersion::V4(a) => (u32::from(*a).into(), 32),
+        IpVersion::V6(a) => (u128::from(*a), 128),
+    }
+}
+
+pub(crate) fn from_bits(bits: u128, width: u8) -> IpVersion {
+    if width == 32 {
+        IpVersion::V4(Ipv4Addr::from(bits as u32))
+    } else {
+        IpVersion::V6(Ipv6Addr::from(bits))
+    }
+}
+
+// Mask for `prefix` bits within an integer of `width` bits
+pub(crate) fn prefix_mask(prefix: u8, width: u8) -> u128 {
+    if width == 32 {
+        ipv4_mask(prefix).into()
+    } else {
+        ipv6_mask(prefix)
+    }
+}
+
+impl IpVersion {
+    /// Zeroes the bits beyond `v4_prefix` or `v6_prefix` (whichever applies), for
+    /// privacy-preserving logs; `192.168.1.55` with a /24 becomes `192.168.1.0`.
+    pub fn anonymize(&self, v4_prefix: u8, v6_prefix: u8) -> IpVersion {
+        match self {
+            IpVersion::V4(addr) => IpVersion::V4(mask_ipv4(addr, v4_prefix)),
+            IpVersion::V6(addr) => IpVersion::V6(mask_ipv6(addr, v6_prefix)),
+        }
+    }
+
+    /// [`anonymize`](IpVersion::anonymize) to a /24 for IPv4 and /48 for IPv6,
+    /// the usual granularity of GeoIP data.
+    pub fn anonymize_default(&self) -> IpVersion {
+        self.anonymize(24, 48)
+    }
+
+    /// The address `n` further on, or `None` past the end of the family's range.
+    pub fn checked_add(&self, n: u128) -> Option<IpVersion> {
+        let (bits, width) = to_bits(self);
+        let sum = bits.checked_add(n)?;
+        (sum <= prefix_mask(width, width)).then(|| from_bits(sum, width))
+    }
+
+    /// The address `n` back, or `None` before the start of the range.
+    pub fn checked_sub(&self, n: u128) -> Option<IpVersion> {
+        let (bits, width) = to_bits(self);
+        Some(from_bits(bits.checked_sub(n)?, width))
+    }
+}
+
+/// Reduces a list of networks to the minimal set covering the same addresses:
+/// host bits are masked off, networks inside another are dropped, and adjacent
+/// networks of the same size are merged into their parent, repeatedly. IPv4
+/// networks come first, then IPv6, each in ascending order. Prefixes longer
+/// than the address family are treated as single hosts.
+pub fn aggregate(cidrs: &[(IpVersion, u8)]) -> Vec<(IpVersion, u8)> {
+    let mut result = Vec::new();
+    for width in [32, 128] {
+        let mut networks: Vec<(u128, u8)> = cidrs
+            .iter()
+            .map(|(addr, prefix)| (to_bits(addr), *prefix))
+            .filter(|((_, w), _)| *w == width)
+            .map(|((bits, _), prefix)| {
+                let prefix = prefix.min(width);
+                (bits & prefix_mask(prefix, width), prefix)
+            })
+            .collect();
+        networks.sort_unstable();
+
+        let mut merged: Vec<(u128, u8)> = Vec::new();
+        for (bits, prefix) in networks {
+            // Sorted by base then prefix, so a containing network is always the last kept
+            if let Some(&(last, last_prefix)) = merged.last()
+                && last_prefix <= prefix
+                && bits & prefix_mask(last_prefix, width) == last
+            {
+                continue;
+            }
+            merged.push((bits, prefix));
+            // Fold sibling pairs into their parent for as long as possible
+            while let [.., (a, a_prefix), (b, b_prefix)] = merged[..]
+                && a_prefix == b_prefix
+                && a_prefix > 0
+                && a & prefix_mask(a_prefix - 1, width) == a
+                && a | (1 << (width - a_prefix)) == b
+            {
+                merged.truncate(merged.len() - 2);
+                merged.push((a, a_prefix - 1));
+            }
+        }
+        result.extend(
+            merged
+                .into_iter()
+                .map(|(bits, prefix)| (from_bits(bits, width), prefix)),
+        );
+    }
+    result
+}
+
+/// Whether `addr` is the network (all host bits zero) address of `net/prefix`.
+/// Point-to-point /31 and host /32 networks have no network address (RFC 3021),
+/// so this is always false for them.
+pub fn is_network_address(addr: &Ipv4Addr, net: &Ipv4Addr, prefix: u8) -> bool {
+    prefix < 31 && *addr == mask_ipv4(net, prefix)
+}
+
+/// Whether `addr` is the broadcast (all host bits one) address of `net/prefix`.
+/// As with [`is_network_address`], /31 and /32 networks have none.
+pub fn is_broadcast_address(addr: &Ipv4Addr, net: &Ipv4Addr, prefix: u8) -> bool {
+    if prefix >= 31 {
+        return false;
+    }
+    let broadcast = u32::from(mask_ipv4(net, prefix)) | !ipv4_mask(prefix);
+    u32::from(*addr) == broadcast
+}
+
+/// Combines the top 64 bits of `prefix` with `iface_id` as the low 64 bits, as
+/// SLAAC and DHCPv6 do for addresses within a /64.
+pub fn with_interface_id(prefix: &Ipv6Addr, iface_id: u64) -> IpVersion {
+    IpVersion::V6(Ipv6Addr::from(
+        u128::from(mask_ipv6(prefix, 64)) | u128::from(iface_id),
+    ))
+}
+
+/// The solicited-node multicast address NDP uses for `addr`: `ff02::1:ff00:0/104`
+/// followed by the low 24 bits of the address (RFC 4291 section 2.7.1).
+pub fn solicited_node_multicast(addr: &Ipv6Addr) -> IpVersion {
+    let base = u128::from(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 1, 0xff00, 0));
+    IpVersion::V6(Ipv6Addr::from(base | (u128::from(*addr) & 0xff_ffff)))
+}
+
+/// How far apart two addresses are as integers, or `None` across families.
+pub fn distance(a: &IpVersion, b: &IpVersion) -> Option<u128> {
+    let ((a, a_width), (b, b_width)) = (to_bits(a), to_bits(b));
+    (a_width == b_width).then(|| a.abs_diff(b))
+}
+
+/// How many leading bits two addresses share, up to 32 or 128, or `None` across
+/// families. This is the longest prefix whose network holds both.
+pub fn common_prefix_len(a: &IpVersion, b: &IpVersion) -> Option<u8> {
+    let ((a, a_width), (b, b_width)) = (to_bits(a), to_bits(b));
+    if a_width != b_width {
+        return None;
+    }
+    let differing = (a ^ b) << (128 - u32::from(a_width));
+    Some(differing.leading_zeros().min(u32::from(a_width)) as u8)
+}
+
+/// Whether two networks share any address. Networks are either nested or
+/// disjoint, so this is whether the wider one contains the other's base.
+/// Networks of different families never overlap.
+pub fn cidrs_overlap(a: (&IpVersion, u8), b: (&IpVersion, u8)) -> bool {
+    in_network(a.0, b.0, a.1.min(b.1))
+}
+
+/// A random address in `network/prefix`, with the `rand` feature: the network
+/// bits are kept and the host bits drawn from `rng`. Any host value can come up,
+/// including the network and broadcast addresses; check with [`is_usable_host`]
+/// if those are unwanted.
+#[cfg(feature = "rand")]
+pub fn random_host_in(network: &IpVersion, prefix: u8, rng: &mut impl rand::RngCore) -> IpVersion {
+    let (bits, width) = to_bits(network);
+    let mask = prefix_mask(prefix, width);
+    let random = u128::from(rng.next_u64()) << 64 | u128::from(rng.next_u64());
+    let host = random & !mask & prefix_mask(width, width);
+    from_bits((bits & mask) | host, width)
+}
+
+/// A scattered but deterministic IPv6 address for index `i`, for building large
+/// test sets without clustering. The high 64 bits are SplitMix64 of `i`, a
+/// bijection, so distinct indices always give distinct addresses; the low 64
+/// bits are its finalizer applied again to the high bits.
+pub fn ipv6_from_index(i: u64) -> IpVersion {
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    let high = mix(i.wrapping_add(0x9e37_79b9_7f4a_7c15));
+    let low = mix(high);
+    IpVersion::V6(Ipv6Addr::from(u128::from(high) << 64 | u128::from(low)))
+}
+
+/// Whether `addr` can be assigned to a host in `net/prefix`: inside the network
+/// and, for IPv4, not its network or broadcast address. /31 point-to-point and
+/// /32 networks have neither, so every address in them is usable. IPv6 has no
+/// broadcast, but its all-zero interface ID is the Subnet-Router anycast
+/// address (RFC 4291), which is excluded except in /127 links (RFC 6164) and
+/// /128s.
+pub fn is_usable_host(addr: &IpVersion, net: &IpVersion, prefix: u8) -> bool {
+    if !in_network(addr, net, prefix) {
+        return false;
+    }
+    match (addr, net) {
+        (IpVersion::V4(a), IpVersion::V4(n)) => {
+            !is_network_address(a, n, prefix) && !is_broadcast_address(a, n, prefix)
+        }
+        (IpVersion::V6(a), _) => {
+            let subnet_router = u128::from(*a) & !prefix_mask(prefix, 128) == 0;
+            prefix >= 127 || !subnet_router
+        }
+        _ => true,
+    }
+}
+
+/// Subnet calculator output for one network, see [`parse_cidr_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CidrInfo {
+    /// The address with its host bits cleared
+    pub network: IpVersion,
+    pub prefix: u8,
+    /// Every address in the network, saturating at `u128::MAX` for an IPv6 /0
+    pub host_count: u128,
+    /// The addresses a host can be given, as [`is_usable_host`] counts them
+    pub usable_count: u128,
+    pub first_host: IpVersion,
+    pub last_host: IpVersion,
+    /// The IPv4 broadcast address; `None` for IPv6 and for /31 and /32
+    pub broadcast: Option<IpVersion>,
+}
+
+/// [`parse_cidr`] and work out the network's size and host range in one go.
+pub fn parse_cidr_info(input: &str) -> Result<CidrInfo, ParseError> {
+    let (addr, prefix) = parse_cidr(input)?;
+    let (bits, width) = to_bits(&addr);
+    let network = bits & prefix_mask(prefix, width);
+    let last = network | (!prefix_mask(prefix, width) & (u128::MAX >> (128 - width)));
+    let host_count = 1u128
+        .checked_shl(u32::from(width - prefix))
+        .unwrap_or(u128::MAX);
+
+    // Only IPv4 networks with room for hosts lose the network and broadcast
+    // addresses, and IPv6 ones the Subnet-Router anycast address
+    let reserved_ends = width == 32 && prefix < 31;
+    let (first_host, last_host, usable_count) = if reserved_ends {
+        (network + 1, last - 1, host_count - 2)
+    } else if width == 128 && prefix < 127 {
+        (network + 1, last, host_count - 1)
+    } else {
+        (network, last, host_count)
+    };
+    Ok(CidrInfo {
+        network: from_bits(network, width),
+        prefix,
+        host_count,
+        usable_count,
+        first_host: from_bits(first_host, width),
+        last_host: from_bits(last_host, width),
+        broadcast: reserved_ends.then(|| from_bits(last, width)),
+    })
+}
+
+/// Parses `address/prefix` notation, checking the prefix fits the address family.
+/// The address is returned as written; host bits are not masked off. A zone is
+/// an error here rather than dropped, see [`parse_cidr_with_zone`]. Whitespace
+/// is ignored, as by [`parse`](crate::parse), so `192.168.1.0 / 24` is accepted.
+pub fn parse_cidr(input: &str) -> Result<(IpVersion, u8), ParseError> {
+    let nospace: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let input = nospace.as_str();
+    let Some((addr_str, prefix_str)) = input.split_once('/') else {
+        return Err(ParseError::MissingPrefix(input.to_string()));
+    };
+    let addr = match IpAddr::from_str(addr_str) {
+        Ok(addr) => IpVersion::from(addr),
+        Err(_) => return Err(ParseError::InvalidAddress(addr_str.to_string())),
+    };
+    let max = match addr {
+        IpVersion::V4(_) => 32,
+        IpVersion::V6(_) => 128,
+    };
+    match prefix_str.parse::<u8>() {
+        Ok(prefix) if prefix <= max && prefix_str.bytes().all(|b| b.is_ascii_digit()) => {
+            Ok((addr, prefix))
+        }
+        _ => Err(ParseError::InvalidPrefix(prefix_str.to_string())),
+    }
+}
+
+/// Splits `network/prefix` into its two halves, returning both and their prefix
+/// length (one longer), or `None` for a single address. Host bits set in
+/// `network` are ignored.
+pub fn split_once(network: &IpVersion, prefix: u8) -> Option<(IpVersion, IpVersion, u8)> {
+    let (bits, width) = to_bits(network);
+    if prefix >= width {
+        return None;
+    }
+    let lower = bits & prefix_mask(prefix, width);
+    let upper = lower | 1 << (width - prefix - 1);
+    Some((from_bits(lower, width), from_bits(upper, width), prefix + 1))
+}
+
+/// The mask for `prefix` in the family of `network`, so `255.255.255.0` for an
+/// IPv4 /24 and `ffff:ffff::` for an IPv6 /32.
+pub fn netmask(network: &IpVersion, prefix: u8) -> IpVersion {
+    let (_, width) = to_bits(network);
+    from_bits(prefix_mask(prefix, width), width)
+}
+
+/// The prefix length a [`netmask`] stands for, so `255.255.255.0` is 24. A mask
+/// whose one bits aren't all at the front, such as `255.0.255.0`, is
+/// [`ParseError::InvalidNetmask`].
+pub fn netmask_to_prefix(mask: &IpVersion) -> Result<u8, ParseError> {
+    let (bits, width) = to_bits(mask);
+    let prefix = (bits << (128 - u32::from(width))).leading_ones() as u8;
+    if prefix_mask(prefix, width) != bits {
+        return Err(ParseError::InvalidNetmask(mask.to_string()));
+    }
+    Ok(prefix)
+}
+
+/// Parses a network with [`parse_cidr`] and returns its [`netmask`] as text.
+pub fn cidr_to_netmask_string(input: &str) -> Result<String, ParseError> {
+    let (network, prefix) = parse_cidr(input)?;
+    Ok(netmask(&network, prefix).to_string())
+}
+
+/// [`parse_cidr`] with the host bits masked off, so `192.168.1.5/24` gives the
+/// network `192.168.1.0/24` it was most likely meant as. Use
+/// [`parse_cidr_canonical_flagged`] to also find out whether any were set.
+pub fn parse_cidr_canonical(input: &str) -> Result<(IpVersion, u8), ParseError> {
+    let (network, prefix, _) = parse_cidr_canonical_flagged(input)?;
+    Ok((network, prefix))
+}
+
+/// [`parse_cidr_canonical`], also returning whether host bits had to be masked
+/// off, so a caller can warn about `192.168.1.5/24` while still accepting it.
+pub fn parse_cidr_canonical_flagged(input: &str) -> Result<(IpVersion, u8, bool), ParseError> {
+    let (addr, prefix) = parse_cidr(input)?;
+    let network = addr.anonymize(prefix, prefix);
+    let host_bits_set = network != addr;
+    Ok((network, prefix, host_bits_set))
+}
+
+/// [`parse_cidr`] rejecting a network address with host bits set, such as
+/// `192.168.1.5/24`, with [`ParseError::HostBitsSet`].
+pub fn parse_cidr_strict(input: &str) -> Result<(IpVersion, u8), ParseError> {
+    let (addr, prefix) = parse_cidr(input)?;
+    if addr.anonymize(prefix, prefix) != addr {
+        return Err(ParseError::HostBitsSet(input.to_string()));
+    }
+    Ok((addr, prefix))
+}
+
+/// [`parse_cidr`] for link-local networks that carry a zone, returning it too.
+/// The zone may follow the address (`fe80::1%eth0/64`) or the whole network
+/// (`fe80::/64%eth0`), but not both. Only IPv6 can have a zone. Whitespace is
+/// ignored around the zone but not inside it, where it makes the zone invalid.
+pub fn parse_cidr_with_zone(input: &str) -> Result<(IpVersion, u8, Option<String>), ParseError> {
+    let Some((addr_str, after)) = input.split_once('%') else {
+        let (addr, prefix) = parse_cidr(input)?;
+        return Ok((addr, prefix, None));
+    };
+    let (zone, network) = match after.split_once('/') {
+        Some((zone, prefix_str)) => (zone, format!("{addr_str}/{prefix_str}")),
+        None => (after, addr_str.to_string()),
+    };
+    if after.contains('%') {
+        return Err(ParseError::MultipleZones(input.to_string()));
+    }
+    let zone = validate_zone(zone.trim())?;
+    let (addr, prefix) = parse_cidr(&network)?;
+    if let IpVersion::V4(_) = addr {
+        return Err(ParseError::InvalidZone(zone.to_string()));
+    }
+    Ok((addr, prefix, Some(zone.to_string())))
+}
+
+/// [`parse_cidr`] also accepting abbreviated IPv4 networks with the trailing
+/// octets left off, so `10/8` is `10.0.0.0/8` and `172.16/12` is `172.16.0.0/12`.
+/// The expansion needs the `/`, a bare `10` is never treated as a network.
+pub fn parse_cidr_shorthand(input: &str) -> Result<(IpVersion, u8), ParseError> {
+    if let Some((addr_str, prefix_str)) = input.split_once('/') {
+        let addr_str = addr_str.trim();
+        let octets = addr_str.split('.').count();
+        if octets < 4 && addr_str.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            let padding = ".0".repeat(4 - octets);
+            return parse_cidr(&format!("{addr_str}{padding}/{prefix_str}"));
+        }
+    }
+    parse_cidr(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_and_broadcast() {
+        let net = Ipv4Addr::new(192, 168, 1, 0);
+        let test_cases = vec![
+            (Ipv4Addr::new(192, 168, 1, 0), true, false),
+            (Ipv4Addr::new(192, 168, 1, 255), false, true),
+            (Ipv4Addr::new(192, 168, 1, 1), false, false),
+            (Ipv4Addr::new(192, 168, 2, 0), false, false), // Outside the network
+        ];
+        for (addr, network, broadcast) in test_cases {
+            assert_eq!(is_network_address(&addr, &net, 24), network);
+            assert_eq!(is_broadcast_address(&addr, &net, 24), broadcast);
+        }
+    }
+
+    #[test]
+    fn point_to_point_has_no_network_or_broadcast() {
+        let net = Ipv4Addr::new(10, 0, 0, 0);
+        for addr in [Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 1)] {
+            assert!(!is_network_address(&addr, &net, 31));
+            assert!(!is_broadcast_address(&addr, &net, 31));
+        }
+    }
+
+    #[test]
+    fn cidr_parsing() {
+        assert_eq!(
+            parse_cidr("192.168.1.0/24"),
+            Ok((IpVersion::V4(Ipv4Addr::new(192, 168, 1, 0)), 24))
+        );
+        assert_eq!(
+            parse_cidr("2001:db8::/32"),
+            Ok((IpVersion::V6("2001:db8::".parse().unwrap()), 32))
+        );
+
+        let test_cases = vec![
+            "192.168.1.0",     // No prefix
+            "192.168.1.0/33",  // Prefix too long for IPv4
+            "2001:db8::/129",  // Prefix too long for IPv6
+            "192.168.1.0/+24", // Sign on the prefix
+            "invalid/24",      // Not an address
+        ];
+        for input in test_cases {
+            assert!(parse_cidr(input).is_err());
+        }
+    }
+
+    #[test]
+    fn interface_id() {
+        let prefix: Ipv6Addr = "2001:db8:1:2:ffff::".parse().unwrap();
+        assert_eq!(
+            with_interface_id(&prefix, 0x0211_22ff_fe33_4455),
+            IpVersion::V6("2001:db8:1:2:211:22ff:fe33:4455".parse().unwrap())
+        );
+        assert_eq!(
+            with_interface_id(&prefix, 1),
+            IpVersion::V6("2001:db8:1:2::1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn address_distance() {
+        let ip = |s: &str| IpVersion::from(s.parse::<std::net::IpAddr>().unwrap());
+        let test_cases = vec![
+            ("10.0.0.1", "10.0.0.5", Some(4)),
+            ("10.0.0.5", "10.0.0.1", Some(4)),
+            ("10.0.0.1", "10.0.0.1", Some(0)),
+            ("0.0.0.0", "255.255.255.255", Some(u32::MAX.into())),
+            (
+                "::",
+                "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+                Some(u128::MAX),
+            ),
+            ("10.0.0.1", "::ffff:10.0.0.5", None),
+        ];
+        for (a, b, expected) in test_cases {
+            assert_eq!(distance(&ip(a), &ip(b)), expected, "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn common_prefix() {
+        let ip = |s: &str| IpVersion::from(s.parse::<std::net::IpAddr>().unwrap());
+        let test_cases = vec![
+            ("10.0.0.0", "10.0.128.0", Some(16)),
+            ("10.0.0.0", "10.0.64.0", Some(17)),
+            ("10.0.0.1", "10.0.0.1", Some(32)),
+            ("0.0.0.0", "128.0.0.0", Some(0)),
+            ("2001:db8::1", "2001:db8::1", Some(128)),
+            ("2001:db8::", "2001:db8:8000::", Some(32)),
+            ("10.0.0.1", "::ffff:10.0.0.1", None),
+        ];
+        for (a, b, expected) in test_cases {
+            assert_eq!(common_prefix_len(&ip(a), &ip(b)), expected, "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn overlap() {
+        let net = |s: &str| parse_cidr(s).unwrap();
+        let test_cases = vec![
+            ("10.0.0.0/24", "10.0.0.128/25", true),
+            ("10.0.0.0/24", "10.0.1.0/24", false),
+            ("10.0.0.0/8", "10.200.0.0/16", true),
+            ("10.0.0.0/24", "10.0.0.0/24", true),
+            ("0.0.0.0/0", "192.168.0.0/16", true),
+            ("2001:db8::/32", "2001:db8:1::/48", true),
+            ("2001:db8::/32", "2001:db9::/32", false),
+            ("0.0.0.0/0", "::/0", false),
+        ];
+        for (a, b, expected) in test_cases {
+            let (a, b) = (net(a), net(b));
+            assert_eq!(
+                cidrs_overlap((&a.0, a.1), (&b.0, b.1)),
+                expected,
+                "{a:?} {b:?}"
+            );
+            assert_eq!(
+                cidrs_overlap((&b.0, b.1), (&a.0, a.1)),
+                expected,
+                "{b:?} {a:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_host() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let test_cases = vec![
+            "10.0.0.0/8",
+            "192.168.1.0/24",
+            "10.0.0.1/32",
+            "2001:db8::/64",
+        ];
+        for input in test_cases {
+            let (net, prefix) = parse_cidr(input).unwrap();
+            for _ in 0..100 {
+                let host = random_host_in(&net, prefix, &mut rng);
+                assert!(in_network(&host, &net, prefix), "{host:?} not in {input}");
+            }
+        }
+        let (net, prefix) = parse_cidr("10.0.0.1/32").unwrap();
+        assert_eq!(random_host_in(&net, prefix, &mut rng), net);
+    }
+
+    #[test]
+    fn from_index() {
+        let addrs: std::collections::HashSet<IpVersion> =
+            (0..10_000).map(ipv6_from_index).collect();
+        assert_eq!(addrs.len(), 10_000);
+        assert_eq!(ipv6_from_index(42), ipv6_from_index(42));
+        // Neighbouring indices land far apart
+        let (a, _) = to_bits(&ipv6_from_index(0));
+        let (b, _) = to_bits(&ipv6_from_index(1));
+        assert!(a.abs_diff(b) > 1 << 100);
+    }
+
+    #[test]
+    fn solicited_node() {
+        let test_cases = vec![
+            ("2001:db8::1:800:200e:8c6c", "ff02::1:ff0e:8c6c"),
+            ("fe80::211:22ff:fe33:4455", "ff02::1:ff33:4455"),
+            ("::1", "ff02::1:ff00:1"),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(
+                solicited_node_multicast(&input.parse().unwrap()),
+                IpVersion::V6(expected.parse().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn split_network() {
+        let test_cases = vec![
+            ("10.0.0.0/24", "10.0.0.0/25", "10.0.0.128/25"),
+            ("10.0.0.77/24", "10.0.0.0/25", "10.0.0.128/25"),
+            ("0.0.0.0/0", "0.0.0.0/1", "128.0.0.0/1"),
+            ("10.0.0.2/31", "10.0.0.2/32", "10.0.0.3/32"),
+            ("2001:db8::/32", "2001:db8::/33", "2001:db8:8000::/33"),
+        ];
+        for (input, lower, upper) in test_cases {
+            let (net, prefix) = parse_cidr(input).unwrap();
+            let (lower, upper) = (parse_cidr(lower).unwrap(), parse_cidr(upper).unwrap());
+            assert_eq!(
+                split_once(&net, prefix),
+                Some((lower.0, upper.0, lower.1)),
+                "{input}"
+            );
+        }
+        let (net, prefix) = parse_cidr("10.0.0.1/32").unwrap();
+        assert_eq!(split_once(&net, prefix), None);
+        let (net, prefix) = parse_cidr("::1/128").unwrap();
+        assert_eq!(split_once(&net, prefix), None);
+    }
+
+    #[test]
+    fn netmask_string() {
+        let test_cases = vec![
+            ("192.168.1.0/24", "255.255.255.0"),
+            ("192.168.1.64/26", "255.255.255.192"),
+            ("10.0.0.0/0", "0.0.0.0"),
+            ("10.0.0.1/32", "255.255.255.255"),
+            ("2001:db8::/32", "ffff:ffff::"),
+            ("2001:db8::/64", "ffff:ffff:ffff:ffff::"),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(cidr_to_netmask_string(input).unwrap(), expected, "{input}");
+        }
+        assert!(cidr_to_netmask_string("10.0.0.0").is_err());
+    }
+
+    #[test]
+    fn netmask_prefix() {
+        let ip = |s: &str| IpVersion::from(s.parse::<std::net::IpAddr>().unwrap());
+        let test_cases = vec![
+            ("255.255.255.0", 24),
+            ("255.255.255.192", 26),
+            ("255.255.255.255", 32),
+            ("0.0.0.0", 0),
+            ("128.0.0.0", 1),
+            ("ffff:ffff::", 32),
+            ("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff", 128),
+            ("::", 0),
+        ];
+        for (mask, expected) in test_cases {
+            assert_eq!(netmask_to_prefix(&ip(mask)), Ok(expected), "{mask}");
+            assert_eq!(netmask(&ip(mask), expected), ip(mask));
+        }
+        let test_cases = vec!["255.0.255.0", "255.255.0.255", "0.0.0.1", "ffff::ffff"];
+        for mask in test_cases {
+            assert_eq!(
+                netmask_to_prefix(&ip(mask)),
+                Err(ParseError::InvalidNetmask(mask.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn cidr_whitespace() {
+        let test_cases = vec![
+            (
+                "192.168.1.0 / 24",
+                IpVersion::V4(Ipv4Addr::new(192, 168, 1, 0)),
+                24,
+            ),
+            (
+                " 10.0.0.0/8\n",
+                IpVersion::V4(Ipv4Addr::new(10, 0, 0, 0)),
+                8,
+            ),
+            (
+                "2001:db8:: / 32",
+                IpVersion::V6("2001:db8::".parse().unwrap()),
+                32,
+            ),
+        ];
+        for (input, addr, prefix) in test_cases {
+            assert_eq!(parse_cidr(input), Ok((addr.clone(), prefix)), "{input:?}");
+            assert_eq!(parse_cidr_shorthand(input), Ok((addr, prefix)), "{input:?}");
+        }
+        assert_eq!(
+            parse_cidr_shorthand("10 / 8"),
+            Ok((IpVersion::V4(Ipv4Addr::new(10, 0, 0, 0)), 8))
+        );
+    }
+
+    #[test]
+    fn cidr_canonical_and_strict() {
+        let network = (IpVersion::V4(Ipv4Addr::new(192, 168, 1, 0)), 24);
+        assert_eq!(parse_cidr_canonical("192.168.1.5/24"), Ok(network.clone()));
+        assert_eq!(parse_cidr_canonical("192.168.1.0/24"), Ok(network.clone()));
+        assert_eq!(
+            parse_cidr_strict("192.168.1.5/24"),
+            Err(ParseError::HostBitsSet("192.168.1.5/24".to_string()))
+        );
+        assert_eq!(parse_cidr_strict("192.168.1.0/24"), Ok(network.clone()));
+        let (addr, prefix) = network;
+        assert_eq!(
+            parse_cidr_canonical_flagged("192.168.1.5/24"),
+            Ok((addr.clone(), prefix, true))
+        );
+        assert_eq!(
+            parse_cidr_canonical_flagged("192.168.1.0/24"),
+            Ok((addr, prefix, false))
+        );
+
+        let v6 = IpVersion::V6("2001:db8::".parse().unwrap());
+        assert_eq!(parse_cidr_canonical("2001:db8::1/32"), Ok((v6.clone(), 32)));
+        assert!(parse_cidr_strict("2001:db8::1/32").is_err());
+        assert_eq!(parse_cidr_strict("2001:db8::/32"), Ok((v6, 32)));
+    }
+
+    #[test]
+    fn cidr_with_zone() {
+        let fe80 = IpVersion::V6("fe80::".parse().unwrap());
+        let eth0 = Some("eth0".to_string());
+        let test_cases = vec![
+            ("fe80::/64%eth0", (fe80.clone(), 64, eth0.clone())),
+            ("fe80::%eth0/64", (fe80.clone(), 64, eth0)),
+            ("fe80::/10", (fe80.clone(), 10, None)),
+            (
+                " fe80:: % eth0 / 64 ",
+                (fe80.clone(), 64, Some("eth0".to_string())),
+            ),
+            ("fe80:: / 64 % eth0", (fe80, 64, Some("eth0".to_string()))),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(parse_cidr_with_zone(input), Ok(expected), "{input}");
+        }
+
+        let test_cases = vec![
+            (
+                "fe80::%eth0/64%eth1",
+                ParseError::MultipleZones("fe80::%eth0/64%eth1".to_string()),
+            ),
+            ("fe80::/64%", ParseError::InvalidZone(String::new())),
+            (
+                "fe80::/64%eth 0",
+                ParseError::InvalidZone("eth 0".to_string()),
+            ),
+            (
+                "fe80::%eth0/129",
+                ParseError::InvalidPrefix("129".to_string()),
+            ),
+            (
+                "10.0.0.0/8%eth0",
+                ParseError::InvalidZone("eth0".to_string()),
+            ),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(parse_cidr_with_zone(input), Err(expected), "{input}");
+        }
+        assert!(parse_cidr("fe80::/64%eth0").is_err());
+    }
+
+    #[test]
+    fn cidr_shorthand() {
+        let test_cases = vec![
+            ("10/8", Ipv4Addr::new(10, 0, 0, 0), 8),
+            ("172.16/12", Ipv4Addr::new(172, 16, 0, 0), 12),
+            ("192.168.1/24", Ipv4Addr::new(192, 168, 1, 0), 24),
+            ("192.168.1.0/24", Ipv4Addr::new(192, 168, 1, 0), 24),
+        ];
+        for (input, addr, prefix) in test_cases {
+            assert_eq!(
+                parse_cidr_shorthand(input),
+                Ok((IpVersion::V4(addr), prefix))
+            );
+        }
+        assert!(parse_cidr_shorthand("10").is_err());
+        assert!(parse_cidr_shorthand("10./8").is_err());
+        assert!(parse_cidr_shorthand("2001:db8::/32").is_ok());
+    }
+
+    #[test]
+    fn aggregation() {
+        let cidr = |input| parse_cidr(input).unwrap();
+        let test_cases = vec![
+            (vec!["10.0.0.0/25", "10.0.0.128/25"], vec!["10.0.0.0/24"]),
+            (
+                vec!["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/23", "10.0.0.5/32"],
+                vec!["10.0.0.0/22"],
+            ),
+            // Adjacent but not siblings, so they can't merge
+            (
+                vec!["10.0.1.0/24", "10.0.2.0/24"],
+                vec!["10.0.1.0/24", "10.0.2.0/24"],
+            ),
+            (
+                vec!["2001:db8:0:1::/64", "192.168.1.77/24", "2001:db8::/64"],
+                vec!["192.168.1.0/24", "2001:db8::/63"],
+            ),
+        ];
+        for (input, expected) in test_cases {
+            let input: Vec<_> = input.into_iter().map(cidr).collect();
+            let expected: Vec<_> = expected.into_iter().map(cidr).collect();
+            assert_eq!(aggregate(&input), expected);
+        }
+    }
+
+    #[test]
+    fn usable_hosts() {
+        let net = IpVersion::V4(Ipv4Addr::new(192, 168, 1, 0));
+        let test_cases = vec![
+            (Ipv4Addr::new(192, 168, 1, 0), 24, false),
+            (Ipv4Addr::new(192, 168, 1, 1), 24, true),
+            (Ipv4Addr::new(192, 168, 1, 254), 24, true),
+            (Ipv4Addr::new(192, 168, 1, 255), 24, false),
+            (Ipv4Addr::new(192, 168, 2, 1), 24, false),
+            (Ipv4Addr::new(192, 168, 1, 0), 31, true),
+            (Ipv4Addr::new(192, 168, 1, 1), 31, true),
+            (Ipv4Addr::new(192, 168, 1, 2), 31, false),
+        ];
+        for (addr, prefix, expected) in test_cases {
+            assert_eq!(
+                is_usable_host(&IpVersion::V4(addr), &net, prefix),
+                expected,
+                "{addr}/{prefix}"
+            );
+        }
+
+        let v6 = |s: &str| IpVersion::V6(s.parse().unwrap());
+        let net = v6("2001:db8::");
+        let test_cases = vec![
+            ("2001:db8::1", 64, true),
+            ("2001:db8::ffff:ffff:ffff:ffff", 64, true),
+            ("2001:db8::", 64, false), // Subnet-Router anycast
+            ("2001:db9::1", 64, false),
+            ("2001:db8::", 127, true),
+            ("2001:db8::1", 127, true),
+            ("2001:db8::", 128, true),
+        ];
+        for (addr, prefix, expected) in test_cases {
+            assert_eq!(
+                is_usable_host(&v6(addr), &net, prefix),
+                expected,
+                "{addr}/{prefix}"
+            );
+        }
+    }
+
+    #[test]
+    fn checked_offsets() {
+        let ip = |s: &str| IpVersion::from(s.parse::<std::net::IpAddr>().unwrap());
+        assert_eq!(ip("192.168.1.0").checked_add(256), Some(ip("192.168.2.0")));
+        assert_eq!(ip("192.168.2.0").checked_sub(256), Some(ip("192.168.1.0")));
+        assert_eq!(
+            ip("255.255.255.254").checked_add(1),
+            Some(ip("255.255.255.255"))
+        );
+        assert_eq!(ip("255.255.255.255").checked_add(1), None);
+        assert_eq!(ip("10.0.0.1").checked_add(u128::MAX), None);
+        assert_eq!(ip("0.0.0.0").checked_sub(1), None);
+        assert_eq!(
+            ip("2001:db8::").checked_add(1 << 64),
+            Some(ip("2001:db8:0:1::"))
+        );
+        assert_eq!(
+            ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").checked_add(1),
+            None
+        );
+        assert_eq!(ip("::").checked_sub(1), None);
+    }
+
+    #[test]
+    fn anonymize() {
+        let v4 = IpVersion::V4(Ipv4Addr::new(192, 168, 1, 55));
+        let v6 = IpVersion::V6("2001:db8:abcd:1234:5678::1".parse().unwrap());
+        assert_eq!(
+            v4.anonymize_default(),
+            IpVersion::V4(Ipv4Addr::new(192, 168, 1, 0))
+        );
+        assert_eq!(
+            v6.anonymize_default(),
+            IpVersion::V6("2001:db8:abcd::".parse().unwrap())
+        );
+        assert_eq!(
+            v4.anonymize(16, 64),
+            IpVersion::V4(Ipv4Addr::new(192, 168, 0, 0))
+        );
+        assert_eq!(
+            v6.anonymize(16, 64),
+            IpVersion::V6("2001:db8:abcd:1234::".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn cidr_info() {
+        let v4 = |a, b, c, d| IpVersion::V4(Ipv4Addr::new(a, b, c, d));
+        assert_eq!(
+            parse_cidr_info("192.168.1.77/24"),
+            Ok(CidrInfo {
+                network: v4(192, 168, 1, 0),
+                prefix: 24,
+                host_count: 256,
+                usable_count: 254,
+                first_host: v4(192, 168, 1, 1),
+                last_host: v4(192, 168, 1, 254),
+                broadcast: Some(v4(192, 168, 1, 255)),
+            })
+        );
+
+        let info = parse_cidr_info("10.0.0.0/31").unwrap();
+        assert_eq!((info.host_count, info.usable_count), (2, 2));
+        assert_eq!(info.broadcast, None);
+
+        let info = parse_cidr_info("2001:db8::/64").unwrap();
+        assert_eq!(info.host_count, 1 << 64);
+        assert_eq!(info.usable_count, (1 << 64) - 1);
+        assert_eq!(
+            info.first_host,
+            IpVersion::V6("2001:db8::1".parse().unwrap())
+        );
+        assert_eq!(
+            info.last_host,
+            IpVersion::V6("2001:db8::ffff:ffff:ffff:ffff".parse().unwrap())
+        );
+        assert_eq!(parse_cidr_info("::/0").unwrap().host_count, u128::MAX);
+
+        assert!(parse_cidr_info("192.168.1.0/33").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{IpVersion, ParseError};
+
+const TAG_V4: u8 = 4;
+const TAG_V6: u8 = 6;
+
+/// Fixed 17 byte storage for either address family: a tag byte followed by the
+/// address bytes (IPv4 is zero padded). Sorting orders all IPv4 before IPv6 and
+/// numerically within a family, which suits large sorted arrays for dedup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompactAddr([u8; 17]);
+
+impl CompactAddr {
+    /// Wraps raw bytes, e.g. read back from storage. The tag is checked when
+    /// converting to [`IpVersion`].
+    pub fn from_bytes(bytes: [u8; 17]) -> Self {
+        CompactAddr(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 17] {
+        &self.0
+    }
+}
+
+impl From<IpVersion> for CompactAddr {
+    fn from(ip: IpVersion) -> Self {
+        let mut bytes = [0u8; 17];
+        match ip {
+            IpVersion::V4(addr) => {
+                bytes[0] = TAG_V4;
+                bytes[1..5].copy_from_slice(&addr.octets());
+            }
+            IpVersion::V6(addr) => {
+                bytes[0] = TAG_V6;
+                bytes[1..].copy_from_slice(&addr.octets());
+            }
+        }
+        CompactAddr(bytes)
+    }
+}
+
+impl TryFrom<CompactAddr> for IpVersion {
+    type Error = ParseError;
+
+    fn try_from(compact: CompactAddr) -> Result<Self, Self::Error> {
+        let bytes = compact.0;
+        match bytes[0] {
+            TAG_V4 if bytes[5..].iter().all(|&b| b == 0) => Ok(IpVersion::V4(Ipv4Addr::new(
+                bytes[1], bytes[2], bytes[3], bytes[4],
+            ))),
+            TAG_V6 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(&bytes[1..]);
+                Ok(IpVersion::V6(Ipv6Addr::from(octets)))
+            }
+            tag => Err(ParseError::InvalidCompactTag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let test_cases = vec![
+            IpVersion::V4(Ipv4Addr::new(192, 168, 1, 1)),
+            IpVersion::V4(Ipv4Addr::UNSPECIFIED),
+            IpVersion::V6("2001:db8::1".parse().unwrap()),
+            IpVersion::V6(Ipv6Addr::LOCALHOST),
+        ];
+        for ip in test_cases {
+            let compact = CompactAddr::from(ip.clone());
+            assert_eq!(IpVersion::try_from(compact), Ok(ip));
+        }
+        assert_eq!(std::mem::size_of::<CompactAddr>(), 17);
+    }
+
+    #[test]
+    fn ordering_and_invalid_tag() {
+        let low = CompactAddr::from(IpVersion::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let high = CompactAddr::from(IpVersion::V4(Ipv4Addr::new(10, 0, 0, 2)));
+        let v6 = CompactAddr::from(IpVersion::V6(Ipv6Addr::UNSPECIFIED));
+        assert!(low < high && high < v6);
+
+        let result: Result<IpVersion, _> = CompactAddr::from_bytes([9; 17]).try_into();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! The individual steps [`parse`](crate::parse) is built from, for building other
+//! grammars on top of this crate. Each `take_*` looks at the front of its input and
+//! returns what it consumed (if anything) along with the remainder.
+
+use crate::{ParseError, parse_port_number, validate_zone};
+
+/// A URL scheme ending in `://`, returning the scheme name. A protocol-relative
+/// `//` is consumed without a scheme. SIP's `sip:` and `sips:` have no `//`.
+pub fn take_scheme(input: &str) -> (Option<&str>, &str) {
+    for sip in ["sip:", "sips:"] {
+        if input.len() > sip.len()
+            && input.is_char_boundary(sip.len())
+            && input[..sip.len()].eq_ignore_ascii_case(sip)
+        {
+            return (Some(&input[..sip.len() - 1]), &input[sip.len()..]);
+        }
+    }
+    if let Some(pos) = input.find("://") {
+        (Some(&input[..pos]), &input[pos + 3..])
+    } else if let Some(rest) = input.strip_prefix("//") {
+        (None, rest)
+    } else {
+        (None, input)
+    }
+}
+
+/// What goes between a scheme and the host: `:` for SIP's `sip` and `sips`,
+/// `://` for anything else, the reverse of [`take_scheme`].
+pub fn scheme_separator(scheme: &str) -> &'static str {
+    if scheme.eq_ignore_ascii_case("sip") || scheme.eq_ignore_ascii_case("sips") {
+        ":"
+    } else {
+        "://"
+    }
+}
+
+/// The host and port of a URL authority, dropping any `user@` userinfo in front
+/// and any `/path`, `?query`, `#fragment` or SIP `;param` after. Meant for what
+/// follows [`take_scheme`].
+pub fn take_authority(input: &str) -> &str {
+    let end = input.find(['/', '?', '#', ';']).unwrap_or(input.len());
+    let authority = &input[..end];
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+}
+
+/// A socket notation prefix such as `inet:`, `tcp4:` or `in_addr_t:`, returning
+/// the prefix name. Only taken when an address follows, and never when the
+/// "prefix" is all hex digits, as then it is the first group of an IPv6 address.
+pub fn take_socket_prefix(input: &str) -> (Option<&str>, &str) {
+    let Some((prefix, addr_part)) = input.split_once(':') else {
+        return (None, input);
+    };
+    let is_prefix = prefix.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !prefix.chars().all(|c| c.is_ascii_hexdigit())
+        && !input.contains('%'); // Not scoped IPv6
+    // The part after the colon has to look like an address, not a port number
+    let is_address =
+        addr_part.contains('.') || addr_part.contains(':') || addr_part.starts_with('[');
+    if is_prefix && is_address {
+        (Some(prefix), addr_part)
+    } else {
+        (None, input)
+    }
+}
+
+/// A bracketed section, returning what is between the brackets. Any bracket
+/// makes this the bracket form, so an unclosed `[::1`, unopened `::1]`, nested
+/// `[[::1]]` or misplaced `x[::1]` is [`ParseError::UnbalancedBrackets`].
+pub fn take_brackets(input: &str) -> Result<Option<(&str, &str)>, ParseError> {
+    let opens = input.matches('[').count();
+    let closes = input.matches(']').count();
+    if opens == 0 && closes == 0 {
+        return Ok(None);
+    }
+    if opens != 1 || closes != 1 || !input.starts_with('[') {
+        return Err(ParseError::UnbalancedBrackets(input.to_string()));
+    }
+    // One of each with `[` first, so the `]` must follow it
+    let close = input.find(']').expect("counted one closing bracket");
+    Ok(Some((&input[1..close], &input[close + 1..])))
+}
+
+/// A zone identifier introduced by `%`, running up to a `:` or the end.
+pub fn take_zone(input: &str) -> Result<(Option<&str>, &str), ParseError> {
+    let Some(after) = input.strip_prefix('%') else {
+        return Ok((None, input));
+    };
+    split_zone(after)
+}
+
+/// [`take_zone`] for a zone inside or just after brackets, where a URL writes the
+/// `%` as `%25` (RFC 6874): `%25eth0` is the zone `eth0`. A bare `%25` is still
+/// the zone `25`.
+pub fn take_bracketed_zone(input: &str) -> Result<(Option<&str>, &str), ParseError> {
+    match input.strip_prefix("%25") {
+        Some(after) if !after.is_empty() && !after.starts_with(':') => split_zone(after),
+        _ => take_zone(input),
+    }
+}
+
+fn split_zone(after: &str) -> Result<(Option<&str>, &str), ParseError> {
+    let (zone, rest) = after.split_at(after.find(':').unwrap_or(after.len()));
+    Ok((Some(validate_zone(zone)?), rest))
+}
+
+/// A `:port` suffix of decimal digits. A `:` ending the input is
+/// [`ParseError::EmptyPort`].
+pub fn take_port(input: &str) -> Result<(Option<u16>, &str), ParseError> {
+    let Some(after) = input.strip_prefix(':') else {
+        return Ok((None, input));
+    };
+    if after.is_empty() {
+        return Err(ParseError::EmptyPort(input.to_string()));
+    }
+    let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+    let (port, rest) = after.split_at(digits);
+    Ok((Some(parse_port_number(port)?), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheme() {
+        assert_eq!(take_scheme("https://10.0.0.1"), (Some("https"), "10.0.0.1"));
+        assert_eq!(take_scheme("//10.0.0.1"), (None, "10.0.0.1"));
+        assert_eq!(take_scheme("10.0.0.1:80"), (None, "10.0.0.1:80"));
+        assert_eq!(
+            take_scheme("SIPS:bob@10.0.0.1"),
+            (Some("SIPS"), "bob@10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn authority() {
+        let test_cases = vec![
+            ("user@[::1]:8443/path?x=1", "[::1]:8443"),
+            ("user:pass@10.0.0.1", "10.0.0.1"),
+            ("10.0.0.1:80?q#f", "10.0.0.1:80"),
+            ("10.0.0.1#top", "10.0.0.1"),
+            ("alice@10.0.0.1:5060;transport=tcp", "10.0.0.1:5060"),
+            ("[fe80::1%eth0]:80", "[fe80::1%eth0]:80"),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(take_authority(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn socket_prefix() {
+        assert_eq!(
+            take_socket_prefix("tcp6:[::1]:22"),
+            (Some("tcp6"), "[::1]:22")
+        );
+        assert_eq!(
+            take_socket_prefix("inet:192.168.1.1"),
+            (Some("inet"), "192.168.1.1")
+        );
+        assert_eq!(take_socket_prefix("2001:db8::1"), (None, "2001:db8::1"));
+        assert_eq!(take_socket_prefix("host:80"), (None, "host:80"));
+        assert_eq!(take_socket_prefix("fe80::1%eth0"), (None, "fe80::1%eth0"));
+    }
+
+    #[test]
+    fn brackets() {
+        assert_eq!(take_brackets("[::1]:80"), Ok(Some(("::1", ":80"))));
+        assert_eq!(take_brackets("[::1]"), Ok(Some(("::1", ""))));
+        assert_eq!(take_brackets("::1"), Ok(None));
+        let test_cases = vec!["[::1", "::1]", "[[::1]]", "]::1[", "x[::1]"];
+        for input in test_cases {
+            assert_eq!(
+                take_brackets(input),
+                Err(ParseError::UnbalancedBrackets(input.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn zone() {
+        assert_eq!(take_zone("%eth0:80"), Ok((Some("eth0"), ":80")));
+        assert_eq!(take_zone("%3"), Ok((Some("3"), "")));
+        assert_eq!(take_zone(":80"), Ok((None, ":80")));
+        assert!(take_zone("%:80").is_err());
+    }
+
+    #[test]
+    fn bracketed_zone() {
+        let test_cases = vec![
+            ("%25eth0:80", Some("eth0"), ":80"),
+            ("%eth0", Some("eth0"), ""),
+            ("%25", Some("25"), ""),
+            ("%25:80", Some("25"), ":80"),
+            (":80", None, ":80"),
+        ];
+        for (input, zone, rest) in test_cases {
+            assert_eq!(take_bracketed_zone(input), Ok((zone, rest)), "{input}");
+        }
+        assert!(take_bracketed_zone("%25%eth0").is_err());
+    }
+
+    #[test]
+    fn port() {
+        assert_eq!(take_port(":8080"), Ok((Some(8080), "")));
+        assert_eq!(take_port(":80/path"), Ok((Some(80), "/path")));
+        assert_eq!(take_port(""), Ok((None, "")));
+        assert!(take_port(":99999").is_err());
+        assert!(take_port(":").is_err());
+    }
+}
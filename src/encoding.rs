@@ -0,0 +1,343 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::{IpVersion, ParseError, ParsedAddr, parse_port_number, validate_zone};
+
+const FLAG_V6: u8 = 0b0001;
+const FLAG_PORT: u8 = 0b0010;
+const FLAG_ZONE: u8 = 0b0100;
+const FLAG_SCHEME: u8 = 0b1000;
+const FLAG_SOCKET_PREFIX: u8 = 0b1_0000;
+
+impl ParsedAddr {
+    /// A compact binary encoding for caching parsed endpoints:
+    ///
+    /// - 1 flag byte: bit 0 IPv6, bit 1 has port, bit 2 has zone, bit 3 has scheme,
+    ///   bit 4 has socket prefix
+    /// - the 4 or 16 address bytes
+    /// - the port, 2 bytes big-endian, if present
+    /// - the zone, the scheme and the socket prefix, if present, each as a 2 byte
+    ///   big-endian length followed by the UTF-8 bytes
+    ///
+    /// Fails with [`ParseError::InvalidEncoding`] if any of those is longer than
+    /// 65535 bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ParseError> {
+        let mut flags = 0;
+        let mut bytes = vec![0];
+        match &self.ip {
+            IpVersion::V4(addr) => bytes.extend_from_slice(&addr.octets()),
+            IpVersion::V6(addr) => {
+                flags |= FLAG_V6;
+                bytes.extend_from_slice(&addr.octets());
+            }
+        }
+        if let Some(port) = self.port {
+            flags |= FLAG_PORT;
+            bytes.extend_from_slice(&port.to_be_bytes());
+        }
+        for (flag, text) in [
+            (FLAG_ZONE, &self.zone),
+            (FLAG_SCHEME, &self.scheme_original),
+            (FLAG_SOCKET_PREFIX, &self.socket_prefix),
+        ] {
+            if let Some(text) = text {
+                flags |= flag;
+                let len = u16::try_from(text.len())
+                    .map_err(|_| ParseError::InvalidEncoding("field longer than 65535 bytes"))?;
+                bytes.extend_from_slice(&len.to_be_bytes());
+                bytes.extend_from_slice(text.as_bytes());
+            }
+        }
+        bytes[0] = flags;
+        Ok(bytes)
+    }
+
+    /// Decodes the [`to_bytes`](ParsedAddr::to_bytes) format, rejecting unknown
+    /// flags, truncated input and trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ParsedAddr, ParseError> {
+        let mut reader = Reader { bytes };
+        let flags = reader.take(1)?[0];
+        if flags & !(FLAG_V6 | FLAG_PORT | FLAG_ZONE | FLAG_SCHEME | FLAG_SOCKET_PREFIX) != 0 {
+            return Err(ParseError::InvalidEncoding("unknown flags"));
+        }
+
+        let ip = if flags & FLAG_V6 != 0 {
+            let octets: [u8; 16] = reader.take(16)?.try_into().expect("took 16 bytes");
+            IpVersion::V6(Ipv6Addr::from(octets))
+        } else {
+            let octets: [u8; 4] = reader.take(4)?.try_into().expect("took 4 bytes");
+            IpVersion::V4(Ipv4Addr::from(octets))
+        };
+        let port = if flags & FLAG_PORT != 0 {
+            Some(u16::from_be_bytes(reader.take_pair()?))
+        } else {
+            None
+        };
+        let zone = if flags & FLAG_ZONE != 0 {
+            Some(reader.take_string()?)
+        } else {
+            None
+        };
+        let scheme = if flags & FLAG_SCHEME != 0 {
+            Some(reader.take_string()?)
+        } else {
+            None
+        };
+        let socket_prefix = if flags & FLAG_SOCKET_PREFIX != 0 {
+            Some(reader.take_string()?)
+        } else {
+            None
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(ParseError::InvalidEncoding("trailing bytes"));
+        }
+        Ok(ParsedAddr {
+            zone,
+            scheme_original: scheme,
+            socket_prefix,
+            ..ParsedAddr::new(ip, port)
+        })
+    }
+
+    /// One CSV row, `version,address,port,zone,scheme`: version is `4` or `6`,
+    /// the address is in its canonical form and absent fields are empty. Fields
+    /// are quoted as RFC 4180 describes when they need it. The socket prefix is
+    /// not part of the layout.
+    pub fn to_csv_record(&self) -> String {
+        let version = match self.ip {
+            IpVersion::V4(_) => "4",
+            IpVersion::V6(_) => "6",
+        };
+        let fields = [
+            version.to_string(),
+            self.ip.to_string(),
+            self.port_str().unwrap_or_default(),
+            self.zone.clone().unwrap_or_default(),
+            self.scheme_original.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_escape(field)).collect();
+        fields.join(",")
+    }
+
+    /// Reads a [`to_csv_record`](ParsedAddr::to_csv_record) row, with or
+    /// without a trailing line ending. A row with the wrong number of fields,
+    /// bad quoting or a version that doesn't match the address is
+    /// [`ParseError::InvalidCsvRecord`], as is a zone on an IPv4 address; an
+    /// address that isn't one at all is [`ParseError::InvalidAddress`].
+    pub fn from_csv_record(record: &str) -> Result<ParsedAddr, ParseError> {
+        let invalid = || ParseError::InvalidCsvRecord(record.to_string());
+        let line = record.trim_end_matches(['\r', '\n']);
+        let fields = csv_fields(line).ok_or_else(invalid)?;
+        let [version, address, port, zone, scheme] =
+            <[String; 5]>::try_from(fields).map_err(|_| invalid())?;
+
+        let ip = match address.parse::<IpAddr>() {
+            Ok(ip) => IpVersion::from(ip),
+            Err(_) => return Err(ParseError::InvalidAddress(address)),
+        };
+        let family_matches = match ip {
+            IpVersion::V4(_) => version == "4",
+            IpVersion::V6(_) => version == "6",
+        };
+        if !family_matches {
+            return Err(invalid());
+        }
+        let port = match port.as_str() {
+            "" => None,
+            port => Some(parse_port_number(port)?),
+        };
+        let optional = |field: String| (!field.is_empty()).then_some(field);
+        let zone = optional(zone);
+        if let Some(zone) = &zone {
+            if matches!(ip, IpVersion::V4(_)) {
+                return Err(invalid());
+            }
+            validate_zone(zone)?;
+        }
+        Ok(ParsedAddr {
+            zone,
+            scheme_original: optional(scheme),
+            ..ParsedAddr::new(ip, port)
+        })
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Splits a CSV line into its fields, undoing the quoting, or `None` if a quoted
+// field is unterminated or has anything but a comma after its closing quote
+fn csv_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut field = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    '"' => break,
+                    c => field.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|&c| c != ',') {
+                if c == '"' {
+                    return None;
+                }
+                field.push(c);
+            }
+        }
+        fields.push(field);
+        match chars.next() {
+            Some(',') => continue,
+            None => return Some(fields),
+            Some(_) => return None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.bytes.len() < n {
+            return Err(ParseError::InvalidEncoding("truncated input"));
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_pair(&mut self) -> Result<[u8; 2], ParseError> {
+        Ok(self.take(2)?.try_into().expect("took 2 bytes"))
+    }
+
+    fn take_string(&mut self) -> Result<String, ParseError> {
+        let len = u16::from_be_bytes(self.take_pair()?);
+        let text = self.take(len.into())?;
+        String::from_utf8(text.to_vec()).map_err(|_| ParseError::InvalidEncoding("invalid UTF-8"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_full;
+
+    #[test]
+    fn bytes_round_trip() {
+        let ips = vec![
+            IpVersion::V4(Ipv4Addr::new(192, 168, 1, 1)),
+            IpVersion::V6("fe80::1".parse().unwrap()),
+        ];
+        for ip in ips {
+            for port in [None, Some(8080)] {
+                for zone in [None, Some("eth0")] {
+                    for scheme in [None, Some("https")] {
+                        let mut addr = ParsedAddr::new(ip.clone(), port);
+                        addr.zone = zone.map(str::to_string);
+                        addr.scheme_original = scheme.map(str::to_string);
+                        assert_eq!(
+                            ParsedAddr::from_bytes(&addr.to_bytes().unwrap()),
+                            Ok(addr.clone())
+                        );
+                        addr.socket_prefix = Some("tcp6".to_string());
+                        assert_eq!(ParsedAddr::from_bytes(&addr.to_bytes().unwrap()), Ok(addr));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bytes_invalid() {
+        let addr = ParsedAddr::new(IpVersion::V4(Ipv4Addr::LOCALHOST), Some(80)).with_zone("eth0");
+        let bytes = addr.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 1 + 4 + 2 + 2 + 4);
+
+        let long_zone = parse_full(&format!("fe80::1%{}", "a".repeat(70000))).unwrap();
+        assert_eq!(
+            long_zone.to_bytes(),
+            Err(ParseError::InvalidEncoding("field longer than 65535 bytes"))
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        let test_cases = vec![
+            &bytes[..bytes.len() - 1], // Truncated zone
+            &bytes[..3],               // Truncated address
+            &[][..],                   // Empty
+            &[0x80, 1, 2, 3, 4][..],   // Unknown flag
+            &trailing[..],
+        ];
+        for input in test_cases {
+            assert!(ParsedAddr::from_bytes(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let addr = ParsedAddr::new(IpVersion::V6("fe80::1".parse().unwrap()), Some(8080))
+            .with_zone("eth0")
+            .with_scheme("HTTPS");
+        let record = addr.to_csv_record();
+        assert_eq!(record, "6,fe80::1,8080,eth0,HTTPS");
+        assert_eq!(ParsedAddr::from_csv_record(&record), Ok(addr.clone()));
+        assert_eq!(
+            ParsedAddr::from_csv_record(&format!("{record}\r\n")),
+            Ok(addr)
+        );
+
+        let plain = ParsedAddr::new(IpVersion::V4(Ipv4Addr::new(10, 0, 0, 1)), None);
+        assert_eq!(plain.to_csv_record(), "4,10.0.0.1,,,");
+        assert_eq!(ParsedAddr::from_csv_record("4,10.0.0.1,,,"), Ok(plain));
+
+        // Fields needing quotes survive the trip
+        let odd = ParsedAddr::new(IpVersion::V4(Ipv4Addr::LOCALHOST), Some(80))
+            .with_scheme("say \"hi\", then");
+        let record = odd.to_csv_record();
+        assert_eq!(record, "4,127.0.0.1,80,,\"say \"\"hi\"\", then\"");
+        assert_eq!(ParsedAddr::from_csv_record(&record), Ok(odd));
+    }
+
+    #[test]
+    fn csv_invalid() {
+        let test_cases = vec![
+            "4,10.0.0.1,,",           // Too few fields
+            "4,10.0.0.1,,,,",         // Too many fields
+            "6,10.0.0.1,,,",          // Version mismatch
+            "5,10.0.0.1,,,",          // Unknown version
+            "4,10.0.0.1,,\"eth0,",    // Unterminated quote
+            "4,10.0.0.1,,\"eth0\"x,", // Text after closing quote
+            "4,10.0\"0.1,,,",         // Stray quote
+            "4,not an ip,,,",
+            "4,10.0.0.1,99999,,",
+        ];
+        for input in test_cases {
+            assert!(ParsedAddr::from_csv_record(input).is_err(), "{input}");
+        }
+        let test_cases = vec![
+            ("6,10.0.0.1,,,", "InvalidCsvRecord"),
+            ("4,::1,,,", "InvalidCsvRecord"),
+            ("5,10.0.0.1,,,", "InvalidCsvRecord"),
+            ("4,10.0.0.1,,", "InvalidCsvRecord"),
+            ("4,not an ip,,,", "InvalidAddress"),
+            ("4,10.0.0.1,99999,,", "PortOutOfRange"),
+            ("4,10.0.0.1,80,bad zone!,http", "InvalidCsvRecord"),
+            ("4,10.0.0.1,80,eth0,http", "InvalidCsvRecord"),
+            ("6,fe80::1,80,bad zone!,http", "InvalidZone"),
+        ];
+        for (input, kind) in test_cases {
+            let err = ParsedAddr::from_csv_record(input).unwrap_err();
+            assert_eq!(err.kind(), kind, "{input}");
+        }
+    }
+}
@@ -0,0 +1,389 @@
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+/// Why an input could not be parsed. Each variant carries the offending piece
+/// of input so the `Display` message can point at it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidAddress(String),
+    InvalidBracketedAddress(String),
+    UnbalancedBrackets(String),
+    NotDottedQuad(String),
+    Ipv4MappedRejected(String),
+    /// An address of a family left out of
+    /// [`ParseOptions::allowed_families`](crate::ParseOptions::allowed_families)
+    FamilyNotAllowed(String),
+    InvalidPort(String),
+    /// A port number over 65535
+    PortOutOfRange(String),
+    /// A `:` with no port number after it
+    EmptyPort(String),
+    MultiplePorts(String),
+    InvalidZone(String),
+    MultipleZones(String),
+    UnexpectedTrailing(String),
+    InvalidHostname(String),
+    NonAsciiHostname(String),
+    /// A byte that isn't ASCII, at this position in the input
+    NonAscii(usize),
+    Unresolved(String),
+    MissingPrefix(String),
+    InvalidPrefix(String),
+    InvalidNetmask(String),
+    HostBitsSet(String),
+    InvalidAttribute(String),
+    InvalidWeight(String),
+    UndefinedVariable(String),
+    UnterminatedVariable(String),
+    InvalidCompactTag(u8),
+    InvalidEncoding(&'static str),
+    /// A record not in the [`to_csv_record`](crate::ParsedAddr::to_csv_record) layout
+    InvalidCsvRecord(String),
+    InputTooLong {
+        len: usize,
+        max: usize,
+    },
+    /// An entry of a list failed, see [`parse_list`](crate::parse_list)
+    InvalidListElement {
+        element_index: usize,
+        span: (usize, usize),
+        source: Box<ParseError>,
+    },
+    BufferTooSmall(usize),
+    /// A file couldn't be read, see [`parse_from_paths`](crate::parse_from_paths)
+    #[cfg(feature = "std")]
+    FileRead {
+        path: PathBuf,
+        kind: std::io::ErrorKind,
+    },
+    /// A line of a file failed, numbered from 1
+    #[cfg(feature = "std")]
+    InvalidFileLine {
+        path: PathBuf,
+        line: usize,
+        source: Box<ParseError>,
+    },
+}
+
+impl ParseError {
+    /// The variant name, for grouping errors without matching on them.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParseError::InvalidAddress(_) => "InvalidAddress",
+            ParseError::InvalidBracketedAddress(_) => "InvalidBracketedAddress",
+            ParseError::UnbalancedBrackets(_) => "UnbalancedBrackets",
+            ParseError::NotDottedQuad(_) => "NotDottedQuad",
+            ParseError::Ipv4MappedRejected(_) => "Ipv4MappedRejected",
+            ParseError::FamilyNotAllowed(_) => "FamilyNotAllowed",
+            ParseError::InvalidPort(_) => "InvalidPort",
+            ParseError::PortOutOfRange(_) => "PortOutOfRange",
+            ParseError::EmptyPort(_) => "EmptyPort",
+            ParseError::MultiplePorts(_) => "MultiplePorts",
+            ParseError::InvalidZone(_) => "InvalidZone",
+            ParseError::MultipleZones(_) => "MultipleZones",
+            ParseError::UnexpectedTrailing(_) => "UnexpectedTrailing",
+            ParseError::InvalidHostname(_) => "InvalidHostname",
+            ParseError::NonAsciiHostname(_) => "NonAsciiHostname",
+            ParseError::NonAscii(_) => "NonAscii",
+            ParseError::Unresolved(_) => "Unresolved",
+            ParseError::MissingPrefix(_) => "MissingPrefix",
+            ParseError::InvalidPrefix(_) => "InvalidPrefix",
+            ParseError::InvalidNetmask(_) => "InvalidNetmask",
+            ParseError::HostBitsSet(_) => "HostBitsSet",
+            ParseError::InvalidAttribute(_) => "InvalidAttribute",
+            ParseError::InvalidWeight(_) => "InvalidWeight",
+            ParseError::UndefinedVariable(_) => "UndefinedVariable",
+            ParseError::UnterminatedVariable(_) => "UnterminatedVariable",
+            ParseError::InvalidCompactTag(_) => "InvalidCompactTag",
+            ParseError::InvalidEncoding(_) => "InvalidEncoding",
+            ParseError::InvalidCsvRecord(_) => "InvalidCsvRecord",
+            ParseError::InputTooLong { .. } => "InputTooLong",
+            ParseError::InvalidListElement { .. } => "InvalidListElement",
+            ParseError::BufferTooSmall(_) => "BufferTooSmall",
+            #[cfg(feature = "std")]
+            ParseError::FileRead { .. } => "FileRead",
+            #[cfg(feature = "std")]
+            ParseError::InvalidFileLine { .. } => "InvalidFileLine",
+        }
+    }
+
+    /// Where in the input the error was found, where that is known: the start of
+    /// the failing entry for a list, or a non-ASCII byte.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::NonAscii(pos) => Some(*pos),
+            ParseError::InvalidListElement { span, .. } => Some(span.0),
+            _ => None,
+        }
+    }
+}
+
+/// With the `serde` feature, errors serialize as
+/// `{ "kind": ..., "message": ..., "offset": ... }` from [`ParseError::kind`],
+/// the `Display` message and [`ParseError::offset`], so they can be passed
+/// straight back to an API client.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ParseError", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("offset", &self.offset())?;
+        state.end()
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidAddress(s) => write!(f, "Invalid IP address: {s}"),
+            ParseError::InvalidBracketedAddress(s) => {
+                write!(f, "Invalid IPv6 address in brackets: {s}")
+            }
+            ParseError::UnbalancedBrackets(s) => write!(f, "Unbalanced brackets: {s}"),
+            ParseError::NotDottedQuad(s) => {
+                write!(f, "IPv4 address is not a four part dotted quad: {s}")
+            }
+            ParseError::Ipv4MappedRejected(s) => {
+                write!(f, "IPv4-mapped IPv6 address not allowed: {s}")
+            }
+            ParseError::FamilyNotAllowed(s) => write!(f, "Address family not allowed: {s}"),
+            ParseError::InvalidPort(s) => write!(f, "Invalid port: {s}"),
+            ParseError::PortOutOfRange(s) => write!(f, "Port number over 65535: {s}"),
+            ParseError::EmptyPort(s) if s.is_empty() => write!(f, "Empty port"),
+            ParseError::EmptyPort(s) => write!(f, "Missing port number after colon: {s}"),
+            ParseError::MultiplePorts(s) => write!(f, "More than one port given: {s}"),
+            ParseError::InvalidZone(s) if s.is_empty() => write!(f, "Empty zone identifier"),
+            ParseError::InvalidZone(s) => write!(f, "Invalid zone identifier: {s}"),
+            ParseError::MultipleZones(s) => write!(f, "Multiple zone identifiers: {s}"),
+            ParseError::UnexpectedTrailing(s) => {
+                write!(f, "Unexpected characters after brackets: {s}")
+            }
+            ParseError::InvalidHostname(s) => write!(f, "Invalid hostname: {s}"),
+            ParseError::NonAsciiHostname(s) => {
+                write!(f, "Non-ASCII hostname (enable the `idna` feature): {s}")
+            }
+            ParseError::NonAscii(pos) => write!(f, "Non-ASCII byte at position {pos}"),
+            ParseError::Unresolved(s) => write!(f, "Could not resolve hostname: {s}"),
+            ParseError::MissingPrefix(s) => write!(f, "Missing prefix length: {s}"),
+            ParseError::InvalidPrefix(s) => write!(f, "Invalid prefix length: {s}"),
+            ParseError::InvalidNetmask(s) => write!(f, "Netmask is not contiguous: {s}"),
+            ParseError::HostBitsSet(s) => write!(f, "Network address has host bits set: {s}"),
+            ParseError::InvalidAttribute(s) => {
+                write!(f, "Invalid attribute, expected key=value: {s}")
+            }
+            ParseError::InvalidWeight(s) => write!(f, "Invalid weight: {s}"),
+            ParseError::UndefinedVariable(s) => write!(f, "Undefined variable: {s}"),
+            ParseError::UnterminatedVariable(s) => write!(f, "Unterminated variable: {s}"),
+            ParseError::InvalidCompactTag(tag) => write!(f, "Invalid compact address tag: {tag}"),
+            ParseError::InvalidEncoding(reason) => write!(f, "Invalid binary encoding: {reason}"),
+            ParseError::InvalidCsvRecord(s) => write!(f, "Invalid CSV record: {s}"),
+            ParseError::InputTooLong { len, max } => {
+                write!(f, "Input too long: {len} bytes (maximum {max})")
+            }
+            ParseError::InvalidListElement {
+                element_index,
+                span,
+                source,
+            } => write!(
+                f,
+                "Invalid list element {element_index} at bytes {}..{}: {source}",
+                span.0, span.1
+            ),
+            ParseError::BufferTooSmall(size) => {
+                write!(f, "Output buffer of {size} bytes is too small")
+            }
+            #[cfg(feature = "std")]
+            ParseError::FileRead { path, kind } => {
+                write!(f, "Could not read {}: {kind}", path.display())
+            }
+            #[cfg(feature = "std")]
+            ParseError::InvalidFileLine { path, line, source } => {
+                write!(f, "{}:{line}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::InvalidListElement { source, .. } => Some(source.as_ref()),
+            #[cfg(feature = "std")]
+            ParseError::InvalidFileLine { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let s = String::from;
+        #[allow(unused_mut)]
+        let mut test_cases = vec![
+            (
+                ParseError::InvalidAddress(s("x")),
+                r#"{"kind":"InvalidAddress","message":"Invalid IP address: x","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidBracketedAddress(s("[zz]")),
+                r#"{"kind":"InvalidBracketedAddress","message":"Invalid IPv6 address in brackets: [zz]","offset":null}"#,
+            ),
+            (
+                ParseError::UnbalancedBrackets(s("[::1")),
+                r#"{"kind":"UnbalancedBrackets","message":"Unbalanced brackets: [::1","offset":null}"#,
+            ),
+            (
+                ParseError::NotDottedQuad(s("1.2")),
+                r#"{"kind":"NotDottedQuad","message":"IPv4 address is not a four part dotted quad: 1.2","offset":null}"#,
+            ),
+            (
+                ParseError::Ipv4MappedRejected(s("::ffff:1.2.3.4")),
+                r#"{"kind":"Ipv4MappedRejected","message":"IPv4-mapped IPv6 address not allowed: ::ffff:1.2.3.4","offset":null}"#,
+            ),
+            (
+                ParseError::FamilyNotAllowed(s("::1")),
+                r#"{"kind":"FamilyNotAllowed","message":"Address family not allowed: ::1","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidPort(s("8x")),
+                r#"{"kind":"InvalidPort","message":"Invalid port: 8x","offset":null}"#,
+            ),
+            (
+                ParseError::PortOutOfRange(s("99999")),
+                r#"{"kind":"PortOutOfRange","message":"Port number over 65535: 99999","offset":null}"#,
+            ),
+            (
+                ParseError::EmptyPort(s("")),
+                r#"{"kind":"EmptyPort","message":"Empty port","offset":null}"#,
+            ),
+            (
+                ParseError::EmptyPort(s("10.0.0.1:")),
+                r#"{"kind":"EmptyPort","message":"Missing port number after colon: 10.0.0.1:","offset":null}"#,
+            ),
+            (
+                ParseError::MultiplePorts(s("1.2.3.4:1:2")),
+                r#"{"kind":"MultiplePorts","message":"More than one port given: 1.2.3.4:1:2","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidZone(s("")),
+                r#"{"kind":"InvalidZone","message":"Empty zone identifier","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidZone(s("a b")),
+                r#"{"kind":"InvalidZone","message":"Invalid zone identifier: a b","offset":null}"#,
+            ),
+            (
+                ParseError::MultipleZones(s("[fe80::1%a]%b")),
+                r#"{"kind":"MultipleZones","message":"Multiple zone identifiers: [fe80::1%a]%b","offset":null}"#,
+            ),
+            (
+                ParseError::UnexpectedTrailing(s("x")),
+                r#"{"kind":"UnexpectedTrailing","message":"Unexpected characters after brackets: x","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidHostname(s("-a")),
+                r#"{"kind":"InvalidHostname","message":"Invalid hostname: -a","offset":null}"#,
+            ),
+            (
+                ParseError::NonAsciiHostname(s("é")),
+                r#"{"kind":"NonAsciiHostname","message":"Non-ASCII hostname (enable the `idna` feature): é","offset":null}"#,
+            ),
+            (
+                ParseError::NonAscii(3),
+                r#"{"kind":"NonAscii","message":"Non-ASCII byte at position 3","offset":3}"#,
+            ),
+            (
+                ParseError::Unresolved(s("db")),
+                r#"{"kind":"Unresolved","message":"Could not resolve hostname: db","offset":null}"#,
+            ),
+            (
+                ParseError::MissingPrefix(s("10.0.0.0")),
+                r#"{"kind":"MissingPrefix","message":"Missing prefix length: 10.0.0.0","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidPrefix(s("33")),
+                r#"{"kind":"InvalidPrefix","message":"Invalid prefix length: 33","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidNetmask(s("255.0.255.0")),
+                r#"{"kind":"InvalidNetmask","message":"Netmask is not contiguous: 255.0.255.0","offset":null}"#,
+            ),
+            (
+                ParseError::HostBitsSet(s("10.0.0.1/8")),
+                r#"{"kind":"HostBitsSet","message":"Network address has host bits set: 10.0.0.1/8","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidAttribute(s("k")),
+                r#"{"kind":"InvalidAttribute","message":"Invalid attribute, expected key=value: k","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidWeight(s("x")),
+                r#"{"kind":"InvalidWeight","message":"Invalid weight: x","offset":null}"#,
+            ),
+            (
+                ParseError::UndefinedVariable(s("HOST")),
+                r#"{"kind":"UndefinedVariable","message":"Undefined variable: HOST","offset":null}"#,
+            ),
+            (
+                ParseError::UnterminatedVariable(s("${HOST")),
+                r#"{"kind":"UnterminatedVariable","message":"Unterminated variable: ${HOST","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidCompactTag(9),
+                r#"{"kind":"InvalidCompactTag","message":"Invalid compact address tag: 9","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidEncoding("truncated input"),
+                r#"{"kind":"InvalidEncoding","message":"Invalid binary encoding: truncated input","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidCsvRecord(s("4,x")),
+                r#"{"kind":"InvalidCsvRecord","message":"Invalid CSV record: 4,x","offset":null}"#,
+            ),
+            (
+                ParseError::InputTooLong { len: 300, max: 255 },
+                r#"{"kind":"InputTooLong","message":"Input too long: 300 bytes (maximum 255)","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidListElement {
+                    element_index: 1,
+                    span: (10, 13),
+                    source: Box::new(ParseError::InvalidAddress(s("foo"))),
+                },
+                r#"{"kind":"InvalidListElement","message":"Invalid list element 1 at bytes 10..13: Invalid IP address: foo","offset":10}"#,
+            ),
+            (
+                ParseError::BufferTooSmall(4),
+                r#"{"kind":"BufferTooSmall","message":"Output buffer of 4 bytes is too small","offset":null}"#,
+            ),
+        ];
+        #[cfg(feature = "std")]
+        test_cases.extend([
+            (
+                ParseError::FileRead {
+                    path: "hosts.txt".into(),
+                    kind: std::io::ErrorKind::NotFound,
+                },
+                r#"{"kind":"FileRead","message":"Could not read hosts.txt: entity not found","offset":null}"#,
+            ),
+            (
+                ParseError::InvalidFileLine {
+                    path: "hosts.txt".into(),
+                    line: 2,
+                    source: Box::new(ParseError::InvalidAddress(s("x"))),
+                },
+                r#"{"kind":"InvalidFileLine","message":"hosts.txt:2: Invalid IP address: x","offset":null}"#,
+            ),
+        ]);
+        for (err, expected) in test_cases {
+            assert_eq!(serde_json::to_string(&err).unwrap(), expected, "{err:?}");
+        }
+    }
+}
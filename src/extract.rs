@@ -0,0 +1,115 @@
+use crate::{ParsedAddr, parse_full};
+
+// Characters that can't appear in any accepted address form, so split text on them
+fn is_separator(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            ',' | ';' | '(' | ')' | '<' | '>' | '"' | '\'' | '`' | '|'
+        )
+}
+
+/// Finds every address in free text (log lines, prose, config comments), in the
+/// order they appear. Sentence punctuation after an address is ignored and URLs
+/// give their host, so `see http://10.0.0.1/status.` yields `http://10.0.0.1`.
+pub fn extract_all(input: &str) -> Vec<ParsedAddr> {
+    input
+        .split(is_separator)
+        .filter_map(|token| {
+            let token = token.trim_end_matches(['.', ':', '!', '?']);
+            if token.is_empty() {
+                return None;
+            }
+            // A URL's path is dropped by `parse_full` itself
+            parse_full(token).ok()
+        })
+        .collect()
+}
+
+/// [`extract_all`] that also understands Markdown link syntax. For a link
+/// `[text](target)` the target's address is used, falling back to the text when
+/// the target holds none; an autolink `<http://10.0.0.1>` gives its contents.
+pub fn extract_from_markdown(input: &str) -> Vec<ParsedAddr> {
+    let mut found = Vec::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+    while let Some(offset) = input[pos..].find(['[', '<']) {
+        let start = pos + offset;
+        match markdown_link(&input[start..]) {
+            Some((addrs, consumed)) => {
+                found.extend(extract_all(&input[plain_start..start]));
+                found.extend(addrs);
+                pos = start + consumed;
+                plain_start = pos;
+            }
+            None => pos = start + 1,
+        }
+    }
+    found.extend(extract_all(&input[plain_start..]));
+    found
+}
+
+// The addresses in a link at the start of `input`, and how many bytes it spans
+fn markdown_link(input: &str) -> Option<(Vec<ParsedAddr>, usize)> {
+    if let Some(rest) = input.strip_prefix('<') {
+        let end = rest.find('>')?;
+        let inner = &rest[..end];
+        if inner.is_empty() || inner.contains(char::is_whitespace) {
+            return None;
+        }
+        return Some((extract_all(inner), end + 2));
+    }
+
+    let rest = input.strip_prefix('[')?;
+    let text_end = rest.find("](")?;
+    let text = &rest[..text_end];
+    // `[::1]` followed by prose isn't a link, and a link text can't nest brackets
+    if text.contains(['[', ']']) {
+        return None;
+    }
+    let target_start = text_end + 2;
+    let target_end = target_start + rest[target_start..].find(')')?;
+    let mut addrs = extract_all(&rest[target_start..target_end]);
+    if addrs.is_empty() {
+        addrs = extract_all(text);
+    }
+    Some((addrs, target_end + 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IpVersion;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn extract_from_text() {
+        let found =
+            extract_all("connect to 10.0.0.1:80, or [::1]:8080. See http://192.168.1.1/status.");
+        let expected = vec!["10.0.0.1:80", "[::1]:8080", "http://192.168.1.1"];
+        let found: Vec<String> = found.iter().map(ToString::to_string).collect();
+        assert_eq!(found, expected);
+        assert!(extract_all("nothing to see here: 300.1.1.1 v1.2").is_empty());
+    }
+
+    #[test]
+    fn extract_markdown_links() {
+        let host = IpVersion::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let test_cases = vec![
+            "[10.0.0.1](http://10.0.0.1)",
+            "<http://10.0.0.1>",
+            "[the router](http://10.0.0.1:8080/admin)",
+            "[10.0.0.1](https://example.com)",
+            "Go to <http://10.0.0.1>.",
+        ];
+        for input in test_cases {
+            let found = extract_from_markdown(input);
+            assert_eq!(found.len(), 1, "{input}");
+            assert_eq!(found[0].ip, host);
+        }
+
+        let found = extract_from_markdown("[a](http://10.0.0.1) and [::1]:22 then <10.0.0.2>");
+        let found: Vec<String> = found.iter().map(ToString::to_string).collect();
+        assert_eq!(found, vec!["http://10.0.0.1", "[::1]:22", "10.0.0.2"]);
+    }
+}
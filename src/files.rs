@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use crate::{ParseError, ParsedAddr, parse_full};
+
+/// Parses every address in the given files, with the `std` feature: one per
+/// line, skipping blank lines and `#` comments, whole-line or trailing. A bad
+/// line is [`ParseError::InvalidFileLine`] naming the file and line, and an
+/// unreadable file is [`ParseError::FileRead`].
+pub fn parse_from_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<ParsedAddr>, ParseError> {
+    let mut addrs = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|err| ParseError::FileRead {
+            path: path.to_path_buf(),
+            kind: err.kind(),
+        })?;
+        for (index, line) in contents.lines().enumerate() {
+            let line = line
+                .split_once('#')
+                .map_or(line, |(before, _)| before)
+                .trim();
+            if line.is_empty() {
+                continue;
+            }
+            let addr = parse_full(line).map_err(|err| ParseError::InvalidFileLine {
+                path: path.to_path_buf(),
+                line: index + 1,
+                source: Box::new(err),
+            })?;
+            addrs.push(addr);
+        }
+    }
+    Ok(addrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_paths() {
+        let dir = std::env::temp_dir().join(format!("parse-ip-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("first.txt");
+        let second = dir.join("second.txt");
+        let bad = dir.join("bad.txt");
+        std::fs::write(&first, "# Servers\n10.0.0.1:80\n\n[::1]:443 # local\n").unwrap();
+        std::fs::write(&second, "192.168.1.1\n").unwrap();
+        std::fs::write(&bad, "10.0.0.1\n\n10.0.0.300\n").unwrap();
+
+        let addrs = parse_from_paths(&[&first, &second]).unwrap();
+        let addrs: Vec<String> = addrs.iter().map(ToString::to_string).collect();
+        assert_eq!(addrs, vec!["10.0.0.1:80", "[::1]:443", "192.168.1.1"]);
+
+        let Err(ParseError::InvalidFileLine { path, line, .. }) = parse_from_paths(&[&first, &bad])
+        else {
+            panic!("expected a file line error");
+        };
+        assert_eq!((path, line), (bad.clone(), 3));
+
+        let missing = dir.join("missing.txt");
+        assert_eq!(
+            parse_from_paths(&[&missing]),
+            Err(ParseError::FileRead {
+                path: missing,
+                kind: std::io::ErrorKind::NotFound
+            })
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
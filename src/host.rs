@@ -0,0 +1,257 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use crate::components::take_scheme;
+use crate::{IpVersion, ParseError, parse, parse_full, parse_port_number};
+
+/// A host as given in `host:port` input: either a literal address or a DNS name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Host {
+    Ip(IpVersion),
+    /// An ASCII hostname; internationalized names are converted to their
+    /// punycode `xn--` form when the `idna` feature is enabled.
+    Name(String),
+}
+
+/// Like [`parse`], but also accepts a hostname in place of the address.
+/// Names are checked for DNS syntax only, they are not resolved.
+pub fn parse_host(input: &str) -> Result<(Host, Option<u16>), ParseError> {
+    let ip_err = match parse(input) {
+        Ok((ip, port)) => return Ok((Host::Ip(ip), port)),
+        Err(err) => err,
+    };
+
+    let (_, rest) = take_scheme(input.trim());
+    let (name, port) = match rest.rsplit_once(':') {
+        Some((name, port)) => (name, Some(parse_port_number(port)?)),
+        None => (rest, None),
+    };
+
+    let name = to_ascii(name)?;
+    // A numeric last label means this was meant as an address, so its error is more useful
+    let last_label = name.trim_end_matches('.').rsplit('.').next().unwrap_or("");
+    if !last_label.is_empty() && last_label.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ip_err);
+    }
+    validate_hostname(&name)?;
+    Ok((Host::Name(name), port))
+}
+
+/// [`parse_host`], handing hostnames to `resolver` to turn into an address. This
+/// keeps DNS out of the crate; any lookup (or a fixed table) can be plugged in.
+pub fn parse_resolving(
+    input: &str,
+    resolver: impl Fn(&str) -> Option<IpAddr>,
+) -> Result<(IpVersion, Option<u16>), ParseError> {
+    match parse_host(input)? {
+        (Host::Ip(ip), port) => Ok((ip, port)),
+        (Host::Name(name), port) => match resolver(&name) {
+            Some(addr) => Ok((IpVersion::from(addr), port)),
+            None => Err(ParseError::Unresolved(name)),
+        },
+    }
+}
+
+#[cfg(feature = "idna")]
+fn to_ascii(name: &str) -> Result<String, ParseError> {
+    if name.is_ascii() {
+        return Ok(name.to_string());
+    }
+    idna::domain_to_ascii(name).map_err(|_| ParseError::InvalidHostname(name.to_string()))
+}
+
+#[cfg(not(feature = "idna"))]
+fn to_ascii(name: &str) -> Result<String, ParseError> {
+    if name.is_ascii() {
+        Ok(name.to_string())
+    } else {
+        Err(ParseError::NonAsciiHostname(name.to_string()))
+    }
+}
+
+// RFC 1123 syntax: dot separated labels of letters, digits and inner hyphens
+fn validate_hostname(name: &str) -> Result<(), ParseError> {
+    let labels = name.strip_suffix('.').unwrap_or(name);
+    let valid = !labels.is_empty()
+        && labels.len() <= 253
+        && labels.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidHostname(name.to_string()))
+    }
+}
+
+/// Reads one `/etc/hosts` line, `address hostname [aliases...]`, returning the
+/// address and its names. The address is split off at the first whitespace
+/// before parsing, as the parser would otherwise run everything together.
+/// Blank and `#` comment lines, bad addresses and lines with no names give
+/// `None`.
+pub fn parse_hosts_line(line: &str) -> Option<(IpVersion, Vec<String>)> {
+    let line = line.split_once('#').map_or(line, |(before, _)| before);
+    let mut fields = line.split_whitespace();
+    let addr = parse_full(fields.next()?).ok()?;
+    if addr.port.is_some() {
+        return None;
+    }
+    let names: Vec<String> = fields.map(str::to_string).collect();
+    (!names.is_empty()).then_some((addr.ip, names))
+}
+
+/// Recovers the address from a cloud-generated hostname of the form
+/// `ip-a-b-c-d`, bare or with a domain such as `ip-10-0-0-1.ec2.internal`.
+/// Anything else, including a malformed domain, gives `None`.
+pub fn parse_ec2_hostname(input: &str) -> Option<Ipv4Addr> {
+    let input = input.trim();
+    let label = input.split('.').next()?;
+    let octets = label.strip_prefix("ip-")?;
+    if label.len() < input.len() {
+        validate_hostname(input).ok()?;
+    }
+    octets.replace('-', ".").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosts() {
+        assert_eq!(
+            parse_host("10.0.0.1:80"),
+            Ok((
+                Host::Ip(IpVersion::V4(Ipv4Addr::new(10, 0, 0, 1))),
+                Some(80)
+            ))
+        );
+        assert_eq!(
+            parse_host("example.com:8080"),
+            Ok((Host::Name("example.com".to_string()), Some(8080)))
+        );
+        assert_eq!(
+            parse_host("https://my-host"),
+            Ok((Host::Name("my-host".to_string()), None))
+        );
+
+        let test_cases = vec![
+            "example.com:99999", // Invalid port
+            "-bad.example",      // Leading hyphen
+            "bad..example",      // Empty label
+            "under_score.com",   // Invalid character
+            "300.1.1.1",         // Invalid address, not a name
+        ];
+        for input in test_cases {
+            assert!(parse_host(input).is_err(), "{input}");
+        }
+        assert!(matches!(
+            parse_host("300.1.1.1"),
+            Err(ParseError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn resolving() {
+        let resolver = |name: &str| match name {
+            "localhost" => Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            _ => None,
+        };
+        assert_eq!(
+            parse_resolving("localhost:8080", resolver),
+            Ok((IpVersion::V4(Ipv4Addr::LOCALHOST), Some(8080)))
+        );
+        assert_eq!(
+            parse_resolving("10.0.0.1", resolver),
+            Ok((IpVersion::V4(Ipv4Addr::new(10, 0, 0, 1)), None))
+        );
+        assert_eq!(
+            parse_resolving("unknown.example", resolver),
+            Err(ParseError::Unresolved("unknown.example".to_string()))
+        );
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn idna_hostname() {
+        assert_eq!(
+            parse_host("例え.jp:80"),
+            Ok((Host::Name("xn--r8jz45g.jp".to_string()), Some(80)))
+        );
+    }
+
+    #[cfg(not(feature = "idna"))]
+    #[test]
+    fn non_ascii_hostname_rejected() {
+        assert!(matches!(
+            parse_host("例え.jp:80"),
+            Err(ParseError::NonAsciiHostname(_))
+        ));
+    }
+
+    #[test]
+    fn hosts_line() {
+        let localhost = IpVersion::V4("127.0.0.1".parse().unwrap());
+        let test_cases = vec![
+            (
+                "127.0.0.1 localhost localhost.localdomain",
+                Some((
+                    localhost.clone(),
+                    vec!["localhost", "localhost.localdomain"],
+                )),
+            ),
+            (
+                "\t127.0.0.1\tlocalhost   # loopback",
+                Some((localhost, vec!["localhost"])),
+            ),
+            (
+                "::1 ip6-localhost ip6-loopback",
+                Some((
+                    IpVersion::V6("::1".parse().unwrap()),
+                    vec!["ip6-localhost", "ip6-loopback"],
+                )),
+            ),
+            ("# 10.0.0.1 commented", None),
+            ("", None),
+            ("10.0.0.1", None),
+            ("10.0.0.1:80 web", None),
+            ("not-an-ip host", None),
+        ];
+        for (line, expected) in test_cases {
+            let expected = expected.map(|(ip, names)| {
+                (
+                    ip,
+                    names.into_iter().map(str::to_string).collect::<Vec<_>>(),
+                )
+            });
+            assert_eq!(parse_hosts_line(line), expected, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn ec2_hostname() {
+        let test_cases = vec![
+            ("ip-10-0-0-1", Some(Ipv4Addr::new(10, 0, 0, 1))),
+            ("ip-10-0-0-1.ec2.internal", Some(Ipv4Addr::new(10, 0, 0, 1))),
+            (
+                "ip-172-31-16-254.us-west-2.compute.internal.",
+                Some(Ipv4Addr::new(172, 31, 16, 254)),
+            ),
+            ("ip-10-0-0", None),        // Too few octets
+            ("ip-10-0-0-1-2", None),    // Too many octets
+            ("ip-10-0-0-256", None),    // Octet out of range
+            ("ip-10-0-0-01", None),     // Leading zero
+            ("ip-10-0-0-1..bad", None), // Malformed domain
+            ("host-10-0-0-1", None),    // Wrong prefix
+            ("10.0.0.1", None),         // Plain address
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(parse_ec2_hostname(input), expected, "{input}");
+        }
+    }
+}
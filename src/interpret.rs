@@ -0,0 +1,113 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::{IpVersion, ParseOptions, ParsedAddr, parse_port_number, parse_with_options};
+
+/// Every way `input` can be read as an address, for working out why it parsed
+/// (or didn't) the way it did. Not meant for normal parsing: the readings are
+/// the default mode, each opt-in [`ParseOptions`] mode that accepts more input
+/// (`localhost` under both loopbacks), plus two the parser never picks because
+/// another reading wins:
+///
+/// - an unbracketed IPv6 address with its last group taken as the port, so
+///   `2001:db8::1:8080` is also `[2001:db8::1]:8080`
+/// - a dotted quad with leading zeros read as decimal, so `010.0.0.1` is also
+///   `10.0.0.1` rather than only the `inet_aton` octal `8.0.0.1`
+///
+/// Duplicate readings are listed once, in the order above.
+pub fn parse_all_interpretations(input: &str) -> Vec<ParsedAddr> {
+    let modes = [
+        ParseOptions::default(),
+        ParseOptions {
+            strict_dotted_quad: false,
+            ..Default::default()
+        },
+        ParseOptions {
+            slash_is_port: true,
+            ..Default::default()
+        },
+        ParseOptions {
+            strip_unc_prefix: true,
+            ..Default::default()
+        },
+        ParseOptions {
+            infer_ipv6_port: true,
+            ..Default::default()
+        },
+        ParseOptions {
+            resolve_localhost: true,
+            ..Default::default()
+        },
+        ParseOptions {
+            resolve_localhost: true,
+            localhost_ipv6: true,
+            ..Default::default()
+        },
+    ];
+    let readings = modes
+        .iter()
+        .filter_map(|options| parse_with_options(input, options).ok())
+        .chain(ipv6_last_group_as_port(input.trim()))
+        .chain(decimal_with_leading_zeros(input.trim()));
+
+    let mut found = Vec::new();
+    for addr in readings {
+        if !found.contains(&addr) {
+            found.push(addr);
+        }
+    }
+    found
+}
+
+fn ipv6_last_group_as_port(input: &str) -> Option<ParsedAddr> {
+    let (addr, port) = input.rsplit_once(':')?;
+    let addr = Ipv6Addr::from_str(addr).ok()?;
+    let port = parse_port_number(port).ok()?;
+    Some(ParsedAddr::new(IpVersion::V6(addr), Some(port)))
+}
+
+fn decimal_with_leading_zeros(input: &str) -> Option<ParsedAddr> {
+    let mut octets = [0; 4];
+    let mut parts = input.split('.');
+    for octet in &mut octets {
+        let part = parts.next()?;
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *octet = part.parse().ok()?;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(ParsedAddr::new(IpVersion::V4(Ipv4Addr::from(octets)), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpretations() {
+        let test_cases = vec![
+            ("010.0.0.1", vec!["8.0.0.1", "10.0.0.1"]),
+            (
+                "2001:db8::1:8080",
+                vec!["2001:db8::1:8080", "[2001:db8::1]:8080"],
+            ),
+            ("1.2", vec!["1.0.0.2"]),
+            ("10.0.0.1/80", vec!["10.0.0.1:80"]),
+            ("10.0.0.1", vec!["10.0.0.1"]),
+            ("\\\\10.0.0.1", vec!["10.0.0.1"]),
+            ("2001:db8::1:60000", vec!["[2001:db8::1]:60000"]),
+            ("localhost:80", vec!["127.0.0.1:80", "[::1]:80"]),
+            ("nonsense", vec![]),
+        ];
+        for (input, expected) in test_cases {
+            let found: Vec<String> = parse_all_interpretations(input)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            assert_eq!(found, expected, "{input}");
+        }
+    }
+}
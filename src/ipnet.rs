@@ -0,0 +1,39 @@
+//! Conversions to and from the `ipnet` crate's network type, with the `ipnet`
+//! feature. The orphan rules don't allow `From` impls between `IpNet` and a
+//! `(IpVersion, u8)` tuple, so these are plain functions.
+
+use ipnet::IpNet;
+
+use crate::{IpVersion, ParseError};
+
+/// Converts a `(network, prefix)` pair, such as from [`parse_cidr`](crate::parse_cidr),
+/// into an `IpNet`. Fails if the prefix is too long for the address family.
+pub fn cidr_to_ipnet(cidr: &(IpVersion, u8)) -> Result<IpNet, ParseError> {
+    let (addr, prefix) = cidr;
+    IpNet::new(addr.clone().into(), *prefix)
+        .map_err(|_| ParseError::InvalidPrefix(prefix.to_string()))
+}
+
+/// Converts an `IpNet` into a `(network, prefix)` pair. The address is kept as
+/// written, host bits included, just as [`parse_cidr`](crate::parse_cidr) does.
+pub fn cidr_from_ipnet(net: &IpNet) -> (IpVersion, u8) {
+    (IpVersion::from(net.addr()), net.prefix_len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_cidr;
+
+    #[test]
+    fn ipnet_round_trip() {
+        for input in ["10.0.0.0/8", "192.168.1.5/24", "2001:db8::/32"] {
+            let cidr = parse_cidr(input).unwrap();
+            let net = cidr_to_ipnet(&cidr).unwrap();
+            assert_eq!(net, input.parse::<IpNet>().unwrap());
+            assert_eq!(cidr_from_ipnet(&net), cidr);
+        }
+        let too_long = (IpVersion::V4("10.0.0.0".parse().unwrap()), 33);
+        assert!(cidr_to_ipnet(&too_long).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use crate::{ParseError, ParsedAddr, parse_full};
+
+// Each non-empty element of a comma or newline separated list with its byte span,
+// trimmed of surrounding whitespace
+fn elements(input: &str) -> impl Iterator<Item = (&str, (usize, usize))> {
+    input
+        .split([',', '\n'])
+        .scan(0, |offset, piece| {
+            let start = *offset;
+            *offset += piece.len() + 1;
+            let trimmed = piece.trim_start();
+            let start = start + (piece.len() - trimmed.len());
+            let trimmed = trimmed.trim_end();
+            Some((trimmed, (start, start + trimmed.len())))
+        })
+        .filter(|(element, _)| !element.is_empty())
+}
+
+/// Parses a list of addresses separated by commas and/or newlines, ignoring
+/// empty entries. A bad entry fails the whole list with
+/// [`ParseError::InvalidListElement`], giving its position among the entries and
+/// its byte span in `input` so a caller can point at it.
+pub fn parse_list(input: &str) -> Result<Vec<ParsedAddr>, ParseError> {
+    parsed_elements(input).collect()
+}
+
+/// The first `n` entries of a [`parse_list`] list, for previewing a long one.
+/// Nothing after the `n`th entry is scanned, so it isn't validated either.
+pub fn parse_list_take(input: &str, n: usize) -> Result<Vec<ParsedAddr>, ParseError> {
+    parsed_elements(input).take(n).collect()
+}
+
+/// Tidies a list of addresses into their canonical forms, dropping later
+/// duplicates by [`dedup_key`](crate::dedup_key) and sorting by address then
+/// port, IPv4 first. The first bad input fails the whole list.
+pub fn canonicalize_list(inputs: &[&str]) -> Result<Vec<String>, ParseError> {
+    let mut seen = HashSet::new();
+    let mut addrs = Vec::new();
+    for input in inputs {
+        let addr = parse_full(input)?;
+        if seen.insert(addr.dedup_key()) {
+            addrs.push(addr);
+        }
+    }
+    addrs.sort_by(|a, b| (&a.ip, a.port).cmp(&(&b.ip, b.port)));
+    Ok(addrs.iter().map(ParsedAddr::canonical).collect())
+}
+
+fn parsed_elements(input: &str) -> impl Iterator<Item = Result<ParsedAddr, ParseError>> {
+    elements(input)
+        .enumerate()
+        .map(|(element_index, (element, span))| {
+            parse_full(element).map_err(|err| ParseError::InvalidListElement {
+                element_index,
+                span,
+                source: Box::new(err),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list() {
+        let result = parse_list("10.0.0.1:80, [::1]:443\n192.168.1.1,,").unwrap();
+        let result: Vec<String> = result.iter().map(ToString::to_string).collect();
+        assert_eq!(result, vec!["10.0.0.1:80", "[::1]:443", "192.168.1.1"]);
+        assert_eq!(parse_list(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn list_error_position() {
+        let input = "10.0.0.1, ::1,  300.1.1.1 ,10.0.0.2";
+        let Err(ParseError::InvalidListElement {
+            element_index,
+            span,
+            source,
+        }) = parse_list(input)
+        else {
+            panic!("expected a list element error");
+        };
+        assert_eq!(element_index, 2);
+        assert_eq!(&input[span.0..span.1], "300.1.1.1");
+        assert_eq!(span, (16, 25));
+        assert!(matches!(*source, ParseError::InvalidAddress(_)));
+    }
+
+    #[test]
+    fn list_take() {
+        let input = "10.0.0.1, 10.0.0.2, 10.0.0.3, not an address";
+        let result = parse_list_take(input, 2).unwrap();
+        let result: Vec<String> = result.iter().map(ToString::to_string).collect();
+        assert_eq!(result, vec!["10.0.0.1", "10.0.0.2"]);
+        assert_eq!(parse_list_take(input, 3).unwrap().len(), 3);
+        assert_eq!(parse_list_take(input, 0), Ok(vec![]));
+        assert!(parse_list_take(input, 4).is_err());
+        assert_eq!(parse_list_take("::1", 5).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn canonicalize() {
+        let inputs = [
+            " https://[2001:DB8::1]:443 ",
+            "10.0.0.2:80",
+            "[::ffff:10.0.0.2]:80",
+            "[2001:db8:0:0::1]:443",
+            "10.0.0.2",
+            "fe80::1%eth0",
+            "10.0.0.10",
+            "\u{FEFF}10.0.0.2:80",
+        ];
+        assert_eq!(
+            canonicalize_list(&inputs),
+            Ok(vec![
+                "10.0.0.2".to_string(),
+                "10.0.0.2:80".to_string(),
+                "10.0.0.10".to_string(),
+                "[2001:db8::1]:443".to_string(),
+                "fe80::1%eth0".to_string(),
+            ])
+        );
+        assert_eq!(canonicalize_list(&[]), Ok(vec![]));
+        assert!(matches!(
+            canonicalize_list(&["10.0.0.1", "bogus"]),
+            Err(ParseError::InvalidAddress(_))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt::Write as _;
+
+use crate::IpVersion;
+
+impl IpVersion {
+    /// The reverse DNS (PTR) name: reversed octets under `in-addr.arpa` for IPv4,
+    /// reversed nibbles under `ip6.arpa` for IPv6.
+    pub fn to_ptr_name(&self) -> String {
+        self.ptr_name("ip6.arpa")
+    }
+
+    /// [`to_ptr_name`](IpVersion::to_ptr_name) with the deprecated `ip6.int`
+    /// IPv6 zone (RFC 1886), for old zone files. IPv4 still uses `in-addr.arpa`.
+    pub fn to_ptr_name_legacy(&self) -> String {
+        self.ptr_name("ip6.int")
+    }
+
+    fn ptr_name(&self, ipv6_zone: &str) -> String {
+        match self {
+            IpVersion::V4(addr) => {
+                let [a, b, c, d] = addr.octets();
+                format!("{d}.{c}.{b}.{a}.in-addr.arpa")
+            }
+            IpVersion::V6(addr) => {
+                let mut name = String::with_capacity(64 + ipv6_zone.len());
+                for byte in addr.octets().iter().rev() {
+                    write!(name, "{:x}.{:x}.", byte & 0xf, byte >> 4).expect("writing to a String");
+                }
+                name.push_str(ipv6_zone);
+                name
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    #[test]
+    fn ptr_names() {
+        let (v4, _) = parse("192.168.1.10").unwrap();
+        assert_eq!(v4.to_ptr_name(), "10.1.168.192.in-addr.arpa");
+        assert_eq!(v4.to_ptr_name_legacy(), "10.1.168.192.in-addr.arpa");
+
+        let (v6, _) = parse("2001:db8::567:89ab").unwrap();
+        let nibbles = "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2";
+        assert_eq!(v6.to_ptr_name(), format!("{nibbles}.ip6.arpa"));
+        assert_eq!(v6.to_ptr_name_legacy(), format!("{nibbles}.ip6.int"));
+    }
+}
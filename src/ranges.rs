@@ -0,0 +1,432 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::IpVersion;
+use crate::cidr::in_network;
+
+/// Well-known address blocks, so range definitions live in one place instead of
+/// being scattered across boolean helpers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WellKnown {
+    /// Private IPv4 networks: 10/8, 172.16/12, 192.168/16
+    Rfc1918,
+    /// 127/8 and ::1
+    Loopback,
+    /// 169.254/16 and fe80::/10
+    LinkLocal,
+    /// Carrier-grade NAT shared space, 100.64/10 (RFC 6598)
+    Cgnat,
+    /// fc00::/7 (RFC 4193)
+    UniqueLocalV6,
+    /// 224/4 and ff00::/8
+    Multicast,
+    /// TEST-NET-1/2/3 and 2001:db8::/32
+    Documentation,
+}
+
+const fn v4(a: u8, b: u8, c: u8, d: u8) -> IpVersion {
+    IpVersion::V4(Ipv4Addr::new(a, b, c, d))
+}
+
+const fn v6(first: u16, second: u16) -> IpVersion {
+    IpVersion::V6(Ipv6Addr::new(first, second, 0, 0, 0, 0, 0, 0))
+}
+
+const RFC1918: &[(IpVersion, u8)] = &[
+    (v4(10, 0, 0, 0), 8),
+    (v4(172, 16, 0, 0), 12),
+    (v4(192, 168, 0, 0), 16),
+];
+const LOOPBACK: &[(IpVersion, u8)] = &[
+    (v4(127, 0, 0, 0), 8),
+    (IpVersion::V6(Ipv6Addr::LOCALHOST), 128),
+];
+const LINK_LOCAL: &[(IpVersion, u8)] = &[(v4(169, 254, 0, 0), 16), (v6(0xfe80, 0), 10)];
+const CGNAT: &[(IpVersion, u8)] = &[(v4(100, 64, 0, 0), 10)];
+const UNIQUE_LOCAL_V6: &[(IpVersion, u8)] = &[(v6(0xfc00, 0), 7)];
+const MULTICAST: &[(IpVersion, u8)] = &[(v4(224, 0, 0, 0), 4), (v6(0xff00, 0), 8)];
+const DOCUMENTATION: &[(IpVersion, u8)] = &[
+    (v4(192, 0, 2, 0), 24),
+    (v4(198, 51, 100, 0), 24),
+    (v4(203, 0, 113, 0), 24),
+    (v6(0x2001, 0x0db8), 32),
+];
+
+// RFC 6890 special-purpose registry entries, most specific first so the first
+// match is the right label
+const SPECIAL_PURPOSE: &[(IpVersion, u8, &str)] = &[
+    (v4(0, 0, 0, 0), 8, "This Host on This Network"),
+    (v4(10, 0, 0, 0), 8, "Private-Use"),
+    (v4(100, 64, 0, 0), 10, "Shared Address Space"),
+    (v4(127, 0, 0, 0), 8, "Loopback"),
+    (v4(169, 254, 0, 0), 16, "Link Local"),
+    (v4(172, 16, 0, 0), 12, "Private-Use"),
+    (v4(192, 0, 0, 0), 29, "DS-Lite"),
+    (v4(192, 0, 0, 0), 24, "IETF Protocol Assignments"),
+    (v4(192, 0, 2, 0), 24, "Documentation"),
+    (v4(192, 88, 99, 0), 24, "6to4 Relay Anycast"),
+    (v4(192, 168, 0, 0), 16, "Private-Use"),
+    (v4(198, 18, 0, 0), 15, "Benchmarking"),
+    (v4(198, 51, 100, 0), 24, "Documentation"),
+    (v4(203, 0, 113, 0), 24, "Documentation"),
+    (v4(255, 255, 255, 255), 32, "Limited Broadcast"),
+    (v4(240, 0, 0, 0), 4, "Reserved"),
+    (IpVersion::V6(Ipv6Addr::LOCALHOST), 128, "Loopback"),
+    (IpVersion::V6(Ipv6Addr::UNSPECIFIED), 128, "Unspecified"),
+    (
+        IpVersion::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0xffff, 0, 0)),
+        96,
+        "IPv4-Mapped",
+    ),
+    (
+        IpVersion::V6(Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0)),
+        96,
+        "IPv4-IPv6 Translation",
+    ),
+    (v6(0x0100, 0), 64, "Discard-Only"),
+    (v6(0x2001, 0), 32, "TEREDO"),
+    (v6(0x2001, 0x0002), 48, "Benchmarking"),
+    (v6(0x2001, 0x0db8), 32, "Documentation"),
+    (v6(0x2001, 0x0010), 28, "ORCHID"),
+    (v6(0x2001, 0), 23, "IETF Protocol Assignments"),
+    (v6(0x2002, 0), 16, "6to4"),
+    (v6(0xfc00, 0), 7, "Unique-Local"),
+    (v6(0xfe80, 0), 10, "Linked-Scoped Unicast"),
+];
+
+// Instance metadata services, a favourite server-side request forgery target
+const CLOUD_METADATA: &[IpVersion] = &[
+    v4(169, 254, 169, 254), // AWS, GCP, Azure, Oracle, OpenStack, DigitalOcean
+    v4(169, 254, 170, 2),   // AWS ECS task metadata
+    v4(100, 100, 100, 200), // Alibaba Cloud
+    v4(192, 0, 0, 192),     // Oracle Cloud (legacy)
+    IpVersion::V6(Ipv6Addr::new(0xfd00, 0x0ec2, 0, 0, 0, 0, 0, 0x0254)), // AWS IPv6
+];
+
+/// Why an address is or isn't reachable on the public internet, see
+/// [`IpVersion::routability`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Routability {
+    /// An ordinary public address
+    Global,
+    /// `0.0.0.0` and `::`
+    Unspecified,
+    Loopback,
+    /// RFC 1918 private networks and the RFC 6598 shared CGNAT space
+    Private,
+    LinkLocal,
+    /// IPv6 unique local fc00::/7
+    UniqueLocal,
+    Multicast,
+    Documentation,
+    /// A cloud instance metadata service, see [`IpVersion::is_cloud_metadata`]
+    CloudMetadata,
+    /// Any other special-purpose block, such as 0.0.0.0/8, 240.0.0.0/4,
+    /// benchmarking space or deprecated site-local fec0::/10
+    Reserved,
+}
+
+impl WellKnown {
+    /// The networks making up this block, as `(network, prefix)` pairs.
+    pub fn ranges(self) -> &'static [(IpVersion, u8)] {
+        match self {
+            WellKnown::Rfc1918 => RFC1918,
+            WellKnown::Loopback => LOOPBACK,
+            WellKnown::LinkLocal => LINK_LOCAL,
+            WellKnown::Cgnat => CGNAT,
+            WellKnown::UniqueLocalV6 => UNIQUE_LOCAL_V6,
+            WellKnown::Multicast => MULTICAST,
+            WellKnown::Documentation => DOCUMENTATION,
+        }
+    }
+}
+
+impl IpVersion {
+    /// The RFC 6890 special-purpose registry name for the block this address is
+    /// in, such as `"Loopback"`, `"Shared Address Space"` or `"Documentation"`,
+    /// or `None` for an ordinary address.
+    pub fn special_purpose(&self) -> Option<&'static str> {
+        SPECIAL_PURPOSE
+            .iter()
+            .find(|(net, prefix, _)| in_network(self, net, *prefix))
+            .map(|(_, _, name)| *name)
+    }
+
+    /// Whether this is a well-known cloud instance metadata address, including
+    /// its IPv4-mapped IPv6 spelling. Useful alongside private-range checks when
+    /// guarding against SSRF, since some providers use addresses (like Alibaba's
+    /// `100.100.100.200`) that those checks don't cover.
+    pub fn is_cloud_metadata(&self) -> bool {
+        CLOUD_METADATA.contains(&self.normalize_for_dedup())
+    }
+
+    /// Whether this is an IPv6 form that has been deprecated: site-local
+    /// `fec0::/10` (RFC 3879) or IPv4-compatible `::a.b.c.d` (RFC 4291). `::`
+    /// and `::1` share the compatible shape but are not deprecated. Always false
+    /// for IPv4.
+    pub fn is_deprecated_form(&self) -> bool {
+        let IpVersion::V6(v6) = self else {
+            return false;
+        };
+        let site_local = v6.segments()[0] & 0xffc0 == 0xfec0;
+        let ipv4_compatible =
+            v6.segments()[..6].iter().all(|&s| s == 0) && !v6.is_unspecified() && !v6.is_loopback();
+        site_local || ipv4_compatible
+    }
+
+    /// Which kind of address this is for routing purposes, so a server-side
+    /// request forgery guard can be `addr.routability() == Routability::Global`
+    /// and still tell the user why an address was refused. An IPv6 address
+    /// carrying an IPv4 one (mapped, 6to4, Teredo, NAT64, see
+    /// [`embedded_ipv4`](IpVersion::embedded_ipv4)) is classified as that IPv4
+    /// address. Cloud metadata addresses are reported as such even though most
+    /// are also link-local. Only an address in no special-purpose block is
+    /// `Global`.
+    pub fn routability(&self) -> Routability {
+        if let Some(v4) = self.embedded_ipv4() {
+            return IpVersion::V4(v4).routability();
+        }
+        let is_unspecified = match self {
+            IpVersion::V4(v4) => v4.is_unspecified(),
+            IpVersion::V6(v6) => v6.is_unspecified(),
+        };
+        let in_any = |ranges: &[(IpVersion, u8)]| {
+            ranges
+                .iter()
+                .any(|(net, prefix)| in_network(self, net, *prefix))
+        };
+        if is_unspecified {
+            Routability::Unspecified
+        } else if self.is_cloud_metadata() {
+            Routability::CloudMetadata
+        } else if in_any(LOOPBACK) {
+            Routability::Loopback
+        } else if in_any(RFC1918) || in_any(CGNAT) {
+            Routability::Private
+        } else if in_any(LINK_LOCAL) {
+            Routability::LinkLocal
+        } else if in_any(UNIQUE_LOCAL_V6) {
+            Routability::UniqueLocal
+        } else if in_any(MULTICAST) {
+            Routability::Multicast
+        } else if in_any(DOCUMENTATION) {
+            Routability::Documentation
+        } else if self.special_purpose().is_some() || self.is_deprecated_form() {
+            Routability::Reserved
+        } else {
+            Routability::Global
+        }
+    }
+
+    /// Whether this address is inside the given well-known block.
+    pub fn matches(&self, set: WellKnown) -> bool {
+        set.ranges()
+            .iter()
+            .any(|(net, prefix)| in_network(self, net, *prefix))
+    }
+}
+
+/// The Ethernet multicast MAC address a multicast IP maps to: `01:00:5e` plus
+/// the low 23 bits for IPv4 (RFC 1112), `33:33` plus the low 32 bits for IPv6
+/// (RFC 2464). `None` if the address isn't multicast.
+pub fn multicast_mac(addr: &IpVersion) -> Option<[u8; 6]> {
+    match addr {
+        IpVersion::V4(v4) if v4.is_multicast() => {
+            let [_, b, c, d] = v4.octets();
+            Some([0x01, 0x00, 0x5e, b & 0x7f, c, d])
+        }
+        IpVersion::V6(v6) if v6.is_multicast() => {
+            let [.., a, b, c, d] = v6.octets();
+            Some([0x33, 0x33, a, b, c, d])
+        }
+        _ => None,
+    }
+}
+
+/// Pre-CIDR classful network classes, still used by some legacy tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ipv4Class {
+    /// Leading bit 0: 0.0.0.0 - 127.255.255.255
+    A,
+    /// Leading bits 10: 128.0.0.0 - 191.255.255.255
+    B,
+    /// Leading bits 110: 192.0.0.0 - 223.255.255.255
+    C,
+    /// Leading bits 1110, multicast: 224.0.0.0 - 239.255.255.255
+    D,
+    /// Leading bits 1111, reserved: 240.0.0.0 - 255.255.255.255
+    E,
+}
+
+/// The historical class of an IPv4 address, from its leading bits.
+pub fn ipv4_class(addr: &Ipv4Addr) -> Ipv4Class {
+    match addr.octets()[0].leading_ones() {
+        0 => Ipv4Class::A,
+        1 => Ipv4Class::B,
+        2 => Ipv4Class::C,
+        3 => Ipv4Class::D,
+        _ => Ipv4Class::E,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn routability() {
+        let test_cases = vec![
+            ("8.8.8.8", Routability::Global),
+            ("2606:4700::1111", Routability::Global),
+            ("0.0.0.0", Routability::Unspecified),
+            ("::", Routability::Unspecified),
+            ("127.0.0.1", Routability::Loopback),
+            ("::1", Routability::Loopback),
+            ("10.1.2.3", Routability::Private),
+            ("100.64.0.1", Routability::Private),
+            ("169.254.1.1", Routability::LinkLocal),
+            ("fe80::1", Routability::LinkLocal),
+            ("fd00::1", Routability::UniqueLocal),
+            ("239.1.1.1", Routability::Multicast),
+            ("ff02::1", Routability::Multicast),
+            ("198.51.100.7", Routability::Documentation),
+            ("169.254.169.254", Routability::CloudMetadata),
+            ("::ffff:169.254.169.254", Routability::CloudMetadata),
+            ("::ffff:192.168.1.1", Routability::Private),
+            ("255.255.255.255", Routability::Reserved),
+            ("0.1.2.3", Routability::Reserved),
+            ("198.18.0.1", Routability::Reserved),
+            ("192.0.0.8", Routability::Reserved),
+            ("192.88.99.1", Routability::Reserved),
+            ("100::1", Routability::Reserved),
+            ("2001:2::1", Routability::Reserved),
+            ("fec0::1", Routability::Reserved),
+            ("64:ff9b::7f00:1", Routability::Loopback),
+            ("2002:7f00:1::", Routability::Loopback),
+            (
+                "2001:0:4136:e378:8000:63bf:3fff:fdd2",
+                Routability::Documentation,
+            ), // Teredo
+            ("64:ff9b::8.8.8.8", Routability::Global),
+        ];
+        for (input, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(ip.routability(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn multicast_mac_address() {
+        let test_cases = vec![
+            ("224.0.0.251", Some([0x01, 0x00, 0x5e, 0x00, 0x00, 0xfb])),
+            (
+                "239.255.255.250",
+                Some([0x01, 0x00, 0x5e, 0x7f, 0xff, 0xfa]),
+            ),
+            ("ff02::1:ff00:1", Some([0x33, 0x33, 0xff, 0x00, 0x00, 0x01])),
+            ("ff02::fb", Some([0x33, 0x33, 0x00, 0x00, 0x00, 0xfb])),
+            ("192.168.1.1", None),
+            ("fe80::1", None),
+        ];
+        for (input, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(multicast_mac(&ip), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn well_known_membership() {
+        let test_cases = vec![
+            ("100.64.0.1", WellKnown::Cgnat, true),
+            ("100.127.255.255", WellKnown::Cgnat, true),
+            ("100.128.0.0", WellKnown::Cgnat, false),
+            ("fd12:3456::1", WellKnown::UniqueLocalV6, true),
+            ("fc00::1", WellKnown::UniqueLocalV6, true),
+            ("fe80::1", WellKnown::UniqueLocalV6, false),
+            ("172.31.255.255", WellKnown::Rfc1918, true),
+            ("172.32.0.0", WellKnown::Rfc1918, false),
+            ("::1", WellKnown::Loopback, true),
+            ("fe80::1", WellKnown::LinkLocal, true),
+            ("2001:db8::1", WellKnown::Documentation, true),
+            ("10.0.0.1", WellKnown::UniqueLocalV6, false), // Family mismatch
+        ];
+        for (input, set, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(ip.matches(set), expected, "{input} in {set:?}");
+        }
+    }
+
+    #[test]
+    fn special_purpose_registry() {
+        let test_cases = vec![
+            ("127.0.0.1", Some("Loopback")),
+            ("100.64.1.1", Some("Shared Address Space")),
+            ("198.19.0.1", Some("Benchmarking")),
+            ("203.0.113.9", Some("Documentation")),
+            ("192.0.0.5", Some("DS-Lite")),
+            ("255.255.255.255", Some("Limited Broadcast")),
+            ("fd00::1", Some("Unique-Local")),
+            ("2001:db8::1", Some("Documentation")),
+            ("2001:2::1", Some("Benchmarking")),
+            ("::1", Some("Loopback")),
+            ("8.8.8.8", None),
+            ("2606:4700::1111", None),
+        ];
+        for (input, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(ip.special_purpose(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn classful() {
+        let test_cases = vec![
+            (Ipv4Addr::new(10, 0, 0, 1), Ipv4Class::A),
+            (Ipv4Addr::new(127, 255, 255, 255), Ipv4Class::A),
+            (Ipv4Addr::new(172, 16, 0, 1), Ipv4Class::B),
+            (Ipv4Addr::new(192, 168, 1, 1), Ipv4Class::C),
+            (Ipv4Addr::new(224, 0, 0, 251), Ipv4Class::D),
+            (Ipv4Addr::new(240, 0, 0, 1), Ipv4Class::E),
+            (Ipv4Addr::BROADCAST, Ipv4Class::E),
+        ];
+        for (addr, expected) in test_cases {
+            assert_eq!(ipv4_class(&addr), expected, "{addr}");
+        }
+    }
+
+    #[test]
+    fn cloud_metadata() {
+        let test_cases = vec![
+            ("169.254.169.254", true),
+            ("fd00:ec2::254", true),
+            ("::ffff:169.254.169.254", true),
+            ("100.100.100.200", true),
+            ("169.254.169.253", false),
+            ("10.0.0.1", false),
+        ];
+        for (input, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(ip.is_cloud_metadata(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn deprecated_form() {
+        let test_cases = vec![
+            ("fec0::1", true),
+            ("feff:ffff::1", true),
+            ("::192.168.1.1", true),
+            ("::2", true),
+            ("fe80::1", false),
+            ("2001:db8::1", false),
+            ("::ffff:192.168.1.1", false),
+            ("::1", false),
+            ("::", false),
+            ("192.168.1.1", false),
+        ];
+        for (input, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(ip.is_deprecated_form(), expected, "{input}");
+        }
+    }
+}
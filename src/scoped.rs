@@ -0,0 +1,93 @@
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+use crate::{IpVersion, ParseError, components, parse_full, parse_port_number};
+
+/// Leniently reads a scoped IPv6 endpoint, returning the address, zone and port.
+/// The forms accepted, in the order they are tried:
+///
+/// 1. Bracketed, as [`parse_full`] takes it: `[fe80::1%eth0]:80`, `[fe80::1]%eth0:80`
+/// 2. Unbracketed with a zone and then a port: `fe80::1%eth0:80`. The zone runs
+///    up to the first `:`, so this is unambiguous.
+/// 3. Unbracketed without a zone: `fe80::1`. A trailing `:80` is read as part of
+///    the address, never as a port, as it is in [`parse`](crate::parse).
+///
+/// Any of these can also be followed by whitespace and a separate port number,
+/// as some tools print them (`fe80::1%eth0 80`); giving a port both ways is
+/// [`ParseError::MultiplePorts`].
+pub fn parse_scoped_endpoint(
+    input: &str,
+) -> Result<(Ipv6Addr, Option<String>, Option<u16>), ParseError> {
+    let input = input.trim();
+    let (endpoint, separate_port) = match input.rsplit_once(char::is_whitespace) {
+        Some((endpoint, port)) if port.bytes().all(|b| b.is_ascii_digit()) => {
+            (endpoint.trim_end(), Some(parse_port_number(port)?))
+        }
+        _ => (input, None),
+    };
+
+    let (addr, zone, port) = if endpoint.starts_with('[') {
+        let parsed = parse_full(endpoint)?;
+        let IpVersion::V6(addr) = parsed.ip else {
+            return Err(ParseError::InvalidAddress(endpoint.to_string()));
+        };
+        (addr, parsed.zone, parsed.port)
+    } else {
+        let (addr_part, rest) = endpoint.split_at(endpoint.find('%').unwrap_or(endpoint.len()));
+        let addr = Ipv6Addr::from_str(addr_part)
+            .map_err(|_| ParseError::InvalidAddress(endpoint.to_string()))?;
+        let (zone, rest) = components::take_zone(rest)?;
+        let (port, rest) = components::take_port(rest).map_err(|err| match err {
+            ParseError::EmptyPort(_) => ParseError::EmptyPort(endpoint.to_string()),
+            err => err,
+        })?;
+        if !rest.is_empty() {
+            return Err(ParseError::UnexpectedTrailing(rest.to_string()));
+        }
+        (addr, zone.map(str::to_string), port)
+    };
+
+    match (port, separate_port) {
+        (Some(_), Some(_)) => Err(ParseError::MultiplePorts(input.to_string())),
+        (port, separate_port) => Ok((addr, zone, port.or(separate_port))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_endpoint() {
+        let addr: Ipv6Addr = "fe80::1".parse().unwrap();
+        let eth0 = Some("eth0".to_string());
+        let test_cases = vec![
+            ("fe80::1", (addr, None, None)),
+            ("fe80::1%eth0", (addr, eth0.clone(), None)),
+            ("fe80::1%eth0:8080", (addr, eth0.clone(), Some(8080))),
+            ("fe80::1%eth0 8080", (addr, eth0.clone(), Some(8080))),
+            ("fe80::1  8080", (addr, None, Some(8080))),
+            ("[fe80::1%eth0]:8080", (addr, eth0.clone(), Some(8080))),
+            ("[fe80::1]%eth0:8080", (addr, eth0.clone(), Some(8080))),
+            ("[fe80::1%eth0] 8080", (addr, eth0, Some(8080))),
+            (
+                "fe80::1:8080",
+                ("fe80::1:8080".parse().unwrap(), None, None),
+            ),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(parse_scoped_endpoint(input), Ok(expected), "{input}");
+        }
+
+        let test_cases = vec![
+            "fe80::1%eth0:8080 9090",
+            "[fe80::1]:80 90",
+            "10.0.0.1:80",
+            "fe80::1%",
+            "fe80::1%eth0:80x",
+        ];
+        for input in test_cases {
+            assert!(parse_scoped_endpoint(input).is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::ParsedAddr;
+
+// Sorted by port for the binary search
+const SERVICES: &[(u16, &str)] = &[
+    (20, "ftp-data"),
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "dns"),
+    (67, "dhcp"),
+    (69, "tftp"),
+    (80, "http"),
+    (110, "pop3"),
+    (123, "ntp"),
+    (143, "imap"),
+    (161, "snmp"),
+    (179, "bgp"),
+    (389, "ldap"),
+    (443, "https"),
+    (465, "smtps"),
+    (514, "syslog"),
+    (587, "submission"),
+    (636, "ldaps"),
+    (853, "dns-over-tls"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1194, "openvpn"),
+    (1883, "mqtt"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (5060, "sip"),
+    (5061, "sips"),
+    (5432, "postgresql"),
+    (6379, "redis"),
+    (8080, "http-alt"),
+    (8443, "https-alt"),
+];
+
+/// The usual service name for a well-known port, e.g. 443 is `https`. This is a
+/// small built-in table, not a lookup in `/etc/services`, so it is the same on
+/// every machine.
+pub fn port_service_name(port: u16) -> Option<&'static str> {
+    SERVICES
+        .binary_search_by_key(&port, |&(port, _)| port)
+        .ok()
+        .map(|i| SERVICES[i].1)
+}
+
+/// Whether binding `port` traditionally needs root on Unix: below 1024.
+pub fn is_privileged_port(port: u16) -> bool {
+    port < 1024
+}
+
+impl ParsedAddr {
+    /// Whether there is a port and it [`is_privileged_port`].
+    pub fn has_privileged_port(&self) -> bool {
+        self.port.is_some_and(is_privileged_port)
+    }
+
+    /// The display form with a well-known port written as its
+    /// [`port_service_name`], as in `10.0.0.1:https`. Other ports stay numeric.
+    pub fn to_friendly_string(&self) -> String {
+        let text = self.to_string();
+        match self.port.and_then(port_service_name) {
+            Some(name) => {
+                let host = text.rsplit_once(':').expect("a port is displayed").0;
+                format!("{host}:{name}")
+            }
+            None => text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_full;
+
+    #[test]
+    fn service_names() {
+        assert!(SERVICES.windows(2).all(|w| w[0].0 < w[1].0));
+        let test_cases = vec![
+            (80, Some("http")),
+            (443, Some("https")),
+            (22, Some("ssh")),
+            (53, Some("dns")),
+            (12345, None),
+            (0, None),
+        ];
+        for (port, expected) in test_cases {
+            assert_eq!(port_service_name(port), expected, "{port}");
+        }
+    }
+
+    #[test]
+    fn privileged_port() {
+        let test_cases = vec![(80, true), (1023, true), (1024, false), (8080, false)];
+        for (port, expected) in test_cases {
+            assert_eq!(is_privileged_port(port), expected, "{port}");
+        }
+        assert!(parse_full("10.0.0.1:443").unwrap().has_privileged_port());
+        assert!(!parse_full("10.0.0.1:8443").unwrap().has_privileged_port());
+        assert!(!parse_full("10.0.0.1").unwrap().has_privileged_port());
+    }
+
+    #[test]
+    fn friendly_string() {
+        let test_cases = vec![
+            ("10.0.0.1:443", "10.0.0.1:https"),
+            ("[fe80::1%eth0]:22", "[fe80::1%eth0]:ssh"),
+            ("http://10.0.0.1:8080", "http://10.0.0.1:http-alt"),
+            ("10.0.0.1:12345", "10.0.0.1:12345"),
+            ("::1", "::1"),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(parse_full(input).unwrap().to_friendly_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::IpVersion;
+
+impl IpVersion {
+    /// The IPv4 address carried inside an IPv6 transition address:
+    ///
+    /// - IPv4-mapped `::ffff:a.b.c.d` and IPv4-compatible `::a.b.c.d`: the low 32 bits
+    /// - 6to4 `2002:AABB:CCDD::/48` (RFC 3056): bits 16-48, the site's public IPv4
+    /// - Teredo `2001:0000::/32` (RFC 4380): the client's public IPv4, stored
+    ///   inverted in the low 32 bits
+    /// - NAT64 well-known prefix `64:ff9b::/96` (RFC 6052): the low 32 bits
+    ///
+    /// Plain IPv4 addresses and other IPv6 addresses give `None`.
+    pub fn embedded_ipv4(&self) -> Option<Ipv4Addr> {
+        let IpVersion::V6(addr) = self else {
+            return None;
+        };
+        let bits = u128::from(*addr);
+        let segments = addr.segments();
+        if segments[0] == 0x2002 {
+            return Some(Ipv4Addr::from((bits >> 80) as u32));
+        }
+        if segments[0] == 0x2001 && segments[1] == 0 {
+            return Some(Ipv4Addr::from(!(bits as u32)));
+        }
+        if let Some(v4) = from_nat64(addr, None) {
+            return Some(v4);
+        }
+        if let Some(v4) = addr.to_ipv4_mapped() {
+            return Some(v4);
+        }
+        // `::` and `::1` have the compatible shape but aren't IPv4 carriers
+        if *addr != Ipv6Addr::UNSPECIFIED && *addr != Ipv6Addr::LOCALHOST {
+            return addr.to_ipv4();
+        }
+        None
+    }
+}
+
+/// The 6to4 prefix `2002:AABB:CCDD::` (RFC 3056) for the site with public IPv4
+/// address `addr`; [`IpVersion::embedded_ipv4`] gets the address back.
+pub fn to_6to4(addr: &Ipv4Addr) -> Ipv6Addr {
+    Ipv6Addr::from(0x2002_u128 << 112 | u128::from(u32::from(*addr)) << 80)
+}
+
+const NAT64_WELL_KNOWN: Ipv6Addr = Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0);
+
+/// Embeds `addr` in the low 32 bits of a NAT64 /96 prefix (RFC 6052): the
+/// well-known `64:ff9b::/96` by default, or a network-specific `prefix`, whose
+/// low 32 bits are ignored. The other prefix lengths RFC 6052 allows aren't
+/// supported.
+pub fn to_nat64(addr: &Ipv4Addr, prefix: Option<Ipv6Addr>) -> Ipv6Addr {
+    let prefix = u128::from(prefix.unwrap_or(NAT64_WELL_KNOWN)) & !u128::from(u32::MAX);
+    Ipv6Addr::from(prefix | u128::from(u32::from(*addr)))
+}
+
+/// The IPv4 address [`to_nat64`] embedded in `addr`, or `None` if `addr` isn't
+/// inside the /96 `prefix` (the well-known prefix by default).
+pub fn from_nat64(addr: &Ipv6Addr, prefix: Option<Ipv6Addr>) -> Option<Ipv4Addr> {
+    let prefix = u128::from(prefix.unwrap_or(NAT64_WELL_KNOWN)) >> 32;
+    let bits = u128::from(*addr);
+    (bits >> 32 == prefix).then(|| Ipv4Addr::from(bits as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn embedded_ipv4() {
+        let test_cases = vec![
+            ("2002:c000:201::1", Some(Ipv4Addr::new(192, 0, 2, 1))), // 6to4
+            // Teredo example from RFC 4380: server 65.54.227.120, client 192.0.2.45
+            (
+                "2001:0:4136:e378:8000:63bf:3fff:fdd2",
+                Some(Ipv4Addr::new(192, 0, 2, 45)),
+            ),
+            ("::ffff:192.168.1.1", Some(Ipv4Addr::new(192, 168, 1, 1))), // Mapped
+            ("::10.0.0.1", Some(Ipv4Addr::new(10, 0, 0, 1))),            // Compatible
+            ("64:ff9b::c000:221", Some(Ipv4Addr::new(192, 0, 2, 33))),   // NAT64
+            ("::1", None),
+            ("2001:db8::1", None),
+            ("10.0.0.1", None),
+        ];
+        for (input, expected) in test_cases {
+            let (ip, _) = parse(input).unwrap();
+            assert_eq!(ip.embedded_ipv4(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn six_to_four() {
+        let test_cases = vec![
+            (Ipv4Addr::new(192, 0, 2, 1), "2002:c000:201::"),
+            (Ipv4Addr::new(10, 0, 0, 1), "2002:a00:1::"),
+        ];
+        for (addr, expected) in test_cases {
+            let v6 = to_6to4(&addr);
+            assert_eq!(v6, expected.parse::<Ipv6Addr>().unwrap());
+            assert_eq!(IpVersion::V6(v6).embedded_ipv4(), Some(addr));
+        }
+    }
+
+    #[test]
+    fn nat64() {
+        let v4 = Ipv4Addr::new(192, 0, 2, 33);
+        let embedded = to_nat64(&v4, None);
+        assert_eq!(embedded, "64:ff9b::c000:221".parse::<Ipv6Addr>().unwrap());
+        assert_eq!(from_nat64(&embedded, None), Some(v4));
+
+        let prefix: Ipv6Addr = "2001:db8:64::".parse().unwrap();
+        let embedded = to_nat64(&v4, Some(prefix));
+        assert_eq!(
+            embedded,
+            "2001:db8:64::c000:221".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(from_nat64(&embedded, Some(prefix)), Some(v4));
+        assert_eq!(from_nat64(&embedded, None), None);
+    }
+}